@@ -0,0 +1,297 @@
+/*!
+ * Find approximate occurrences of patterns with a bounded Hamming or edit distance.
+ *
+ * The search is available on [`SuffixArrayWithText`] and on the [`FmIndex`]. Both indices implicitly represent
+ * the trie of all substrings of the text. This trie is traversed depth-first by backtracking, while the
+ * [`Distance`] between the pattern and the current substring is computed incrementally. A branch of the trie is
+ * pruned as soon as the distance cannot drop below the maximum cost anymore.
+ *
+ * * For the [`Distance::Hamming`], only substrings of the same length as the pattern are considered and the cost
+ *   is the number of mismatching positions.
+ * * For the [`Distance::Edit`], substrings of all lengths are considered and the cost is the minimum number of
+ *   substitutions, insertions and deletions that transform the substring into the pattern. The distance is
+ *   computed using one column of the dynamic programming matrix per level of the trie.
+ *
+ * Every distinct substring of the text with a cost of at most the maximum cost is reported once as an
+ * [`ApproximateMatch`], which consists of its suffix array interval, its positions, its length and its cost.
+ * The suffix array is traversed left to right, while the FM-index is traversed right to left using backward
+ * search. Both report the same matches in the same order.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, approximate::Distance};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let matches = res.approximate_matches(b"abba", 1, Distance::Hamming);
+ *
+ * // only "abra" is within one mismatch of "abba"
+ * assert_eq!(matches.len(), 1);
+ * assert_eq!(matches[0].positions(), &[0, 7]);
+ * assert_eq!(matches[0].cost(), 1);
+ *
+ * let matches = res.approximate_matches(b"cd", 1, Distance::Edit);
+ * assert!(matches.iter().any(|m| m.positions() == [4] && m.match_len() == 3));
+ * ```
+ *
+ * The running time depends heavily on the maximum cost and on the size of the alphabet, since the number of
+ * visited nodes of the trie grows exponentially with the maximum cost. This approach is meant for small
+ * maximum costs, as they are common for sequencing data.
+ *
+ * # Sentinel Convention
+ *
+ * The sentinel never matches a symbol of the pattern, so matches never extend beyond the end of the text. For
+ * generalized suffix arrays, zero symbols are treated like any other symbol, like in the [`search`](super::search)
+ * module.
+ */
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement, SmallAlphabet,
+    fm_index::FmIndex,
+    search::{SuffixArrayInterval, child_intervals},
+    suffix_array::SuffixArrayWithText,
+    typestate::BufferMode,
+};
+
+/// The distance measure between a pattern and a substring of the text.
+///
+/// See [`approximate`](self) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Distance {
+    Hamming,
+    Edit,
+}
+
+/// A distinct substring of the text that matches a pattern approximately.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApproximateMatch {
+    interval: SuffixArrayInterval,
+    positions: Vec<usize>,
+    match_len: usize,
+    cost: usize,
+}
+
+impl ApproximateMatch {
+    /// The suffix array interval of the matched substring.
+    pub fn interval(&self) -> SuffixArrayInterval {
+        self.interval
+    }
+
+    /// The positions of the occurrences of the matched substring, sorted by text position.
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// The length of the matched substring.
+    pub fn match_len(&self) -> usize {
+        self.match_len
+    }
+
+    /// The distance between the matched substring and the pattern.
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+}
+
+impl<'s, 't, I: InputElement, O: OutputElement, B: BufferMode>
+    SuffixArrayWithText<'s, 't, I, O, B>
+{
+    /// All distinct substrings of the text with a distance of at most `max_cost` to `pattern`, ordered by
+    /// cost, suffix array interval and length.
+    pub fn approximate_matches(
+        &self,
+        pattern: &[I],
+        max_cost: usize,
+        distance: Distance,
+    ) -> Vec<ApproximateMatch> {
+        let text = self.text;
+        let suffix_array = &self.suffix_array.buffer;
+
+        let mut matches = Vec::new();
+
+        let children = |interval: SuffixArrayInterval, depth: usize| {
+            child_intervals(text, suffix_array, interval, depth)
+        };
+
+        let report = |interval: SuffixArrayInterval, match_len: usize, cost: usize| {
+            let mut positions: Vec<_> = suffix_array[interval.range()]
+                .iter()
+                .map(|&suffix| <usize as NumCast>::from(suffix).unwrap())
+                .collect();
+            positions.sort_unstable();
+
+            matches.push(ApproximateMatch {
+                interval,
+                positions,
+                match_len,
+                cost,
+            });
+        };
+
+        Backtracking {
+            root: SuffixArrayInterval::new(0, text.len()),
+            pattern,
+            max_cost,
+            distance,
+            children,
+            report,
+        }
+        .run();
+
+        sort_matches(&mut matches);
+
+        matches
+    }
+}
+
+impl<I: SmallAlphabet> FmIndex<I> {
+    /// All distinct substrings of the text with a distance of at most `max_cost` to `pattern`, ordered by
+    /// cost, suffix array interval and length.
+    ///
+    /// The positions are computed using the suffix array sample.
+    pub fn approximate_matches(
+        &self,
+        pattern: &[I],
+        max_cost: usize,
+        distance: Distance,
+    ) -> Vec<ApproximateMatch> {
+        let reversed_pattern: Vec<_> = pattern.iter().rev().copied().collect();
+        let mut matches = Vec::new();
+
+        // the nodes are row intervals of the BWT matrix, the substrings are extended to the left
+        let children = |(start_row, end_row): (usize, usize), _depth: usize| {
+            self.symbols_in_rows(start_row, end_row)
+                .into_iter()
+                .map(|symbol| (symbol, self.lf_interval(start_row, end_row, symbol)))
+                .collect()
+        };
+
+        let report = |(start_row, end_row): (usize, usize), match_len: usize, cost: usize| {
+            // the sentinel row is never part of an interval of a non-empty substring
+            let interval = SuffixArrayInterval::new(start_row - 1, end_row - 1);
+            let mut positions: Vec<_> = interval
+                .range()
+                .map(|suffix_array_index| self.suffix_array_entry(suffix_array_index))
+                .collect();
+            positions.sort_unstable();
+
+            matches.push(ApproximateMatch {
+                interval,
+                positions,
+                match_len,
+                cost,
+            });
+        };
+
+        Backtracking {
+            root: (0, self.len() + 1),
+            pattern: &reversed_pattern,
+            max_cost,
+            distance,
+            children,
+            report,
+        }
+        .run();
+
+        sort_matches(&mut matches);
+
+        matches
+    }
+}
+
+// Depth-first traversal of the trie of substrings of a text. The substrings are extended in the
+// direction in which the pattern is given, so for left extensions, the pattern must be reversed.
+struct Backtracking<'p, I, N, C, R> {
+    root: N,
+    pattern: &'p [I],
+    max_cost: usize,
+    distance: Distance,
+    // the children of a node with the given depth and the symbols on the edges
+    children: C,
+    // called for every node with the depth and the cost of its substring
+    report: R,
+}
+
+impl<'p, I, N, C, R> Backtracking<'p, I, N, C, R>
+where
+    I: InputElement,
+    N: Copy,
+    C: FnMut(N, usize) -> Vec<(I, N)>,
+    R: FnMut(N, usize, usize),
+{
+    fn run(mut self) {
+        let root = self.root;
+
+        match self.distance {
+            Distance::Hamming if !self.pattern.is_empty() => self.visit_hamming(root, 0, 0),
+            Distance::Hamming => {}
+            Distance::Edit => {
+                let column: Vec<_> = (0..=self.pattern.len()).collect();
+                self.visit_edit(root, 0, &column);
+            }
+        }
+    }
+
+    fn visit_hamming(&mut self, node: N, depth: usize, cost: usize) {
+        for (symbol, child) in (self.children)(node, depth) {
+            let child_cost = cost + (self.pattern[depth] != symbol) as usize;
+
+            if child_cost > self.max_cost {
+                continue;
+            }
+
+            if depth + 1 == self.pattern.len() {
+                (self.report)(child, depth + 1, child_cost);
+            } else {
+                self.visit_hamming(child, depth + 1, child_cost);
+            }
+        }
+    }
+
+    // column[j] is the edit distance between the substring of the node and the first j symbols of
+    // the pattern
+    fn visit_edit(&mut self, node: N, depth: usize, column: &[usize]) {
+        for (symbol, child) in (self.children)(node, depth) {
+            let child_column = next_edit_distance_column(self.pattern, column, symbol);
+            let cost = *child_column.last().unwrap();
+
+            if cost <= self.max_cost {
+                (self.report)(child, depth + 1, cost);
+            }
+
+            if child_column.iter().any(|&cost| cost <= self.max_cost) {
+                self.visit_edit(child, depth + 1, &child_column);
+            }
+        }
+    }
+}
+
+fn next_edit_distance_column<I: InputElement>(
+    pattern: &[I],
+    column: &[usize],
+    symbol: I,
+) -> Vec<usize> {
+    let mut next_column = Vec::with_capacity(column.len());
+    next_column.push(column[0] + 1);
+
+    for j in 1..column.len() {
+        let substitution = column[j - 1] + (pattern[j - 1] != symbol) as usize;
+        let deletion = column[j] + 1;
+        let insertion = next_column[j - 1] + 1;
+
+        next_column.push(substitution.min(deletion).min(insertion));
+    }
+
+    next_column
+}
+
+fn sort_matches(matches: &mut [ApproximateMatch]) {
+    matches.sort_unstable_by_key(|m| (m.cost, m.interval, m.match_len));
+}
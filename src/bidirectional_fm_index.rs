@@ -0,0 +1,276 @@
+/*!
+ * Extend patterns in both directions using a [bidirectional FM-index] built from the BWTs of a text and its reverse.
+ *
+ * The [`BidirectionalFmIndex`] consists of an [`FmIndex`] of the text and an [`FmIndex`] of the reversed text. A
+ * [`BidirectionalInterval`] stores the synchronized suffix array intervals of a pattern in the text and of the
+ * reversed pattern in the reversed text. It can be extended by one symbol to the left or to the right in
+ * `O(σ)` time for the occurrence table based rank support and `O(log σ)` time for the wavelet matrix based rank
+ * support, where `σ` is the size of the alphabet. This is the basis for many algorithms that need to extend
+ * patterns in both directions, such as the computation of super-maximal exact matches or approximate matching
+ * with search schemes.
+ *
+ * ```
+ * use libsais::bidirectional_fm_index::BidirectionalFmIndex;
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let index = BidirectionalFmIndex::new(text, 4).unwrap();
+ *
+ * // search "cad" starting in the middle
+ * let interval = index.root_interval();
+ * let interval = index.extend_right(interval, b'a');
+ * let interval = index.extend_left(interval, b'c');
+ * let interval = index.extend_right(interval, b'd');
+ *
+ * assert_eq!(interval.len(), 1);
+ * assert_eq!(interval.forward(), index.forward_index().interval(b"cad"));
+ * assert_eq!(interval.reverse(), index.reverse_index().interval(b"dac"));
+ * assert_eq!(index.locate_interval(interval), vec![4]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * Both intervals of a [`BidirectionalInterval`] are intervals of the suffix array in the convention of `libsais`,
+ * i.e. the suffix array without the entry for the sentinel, like the intervals of the [`FmIndex`]. The interval
+ * of the empty pattern contains the whole suffix array.
+ *
+ * [bidirectional FM-index]: https://doi.org/10.1109/BIBM.2009.42
+ */
+
+use crate::{
+    LIBSAIS_I32_OUTPUT_MAXIMUM_SIZE, LibsaisError, SmallAlphabet, bwt::BwtConstruction,
+    fm_index::FmIndex, search::SuffixArrayInterval,
+};
+
+/// A bidirectional FM-index for `u8`/`u16`-based texts.
+///
+/// See [`bidirectional_fm_index`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BidirectionalFmIndex<I: SmallAlphabet> {
+    forward: FmIndex<I>,
+    reverse: FmIndex<I>,
+}
+
+/// The synchronized suffix array intervals of a pattern in the text and of the reversed pattern in the
+/// reversed text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BidirectionalInterval {
+    forward_start: usize,
+    reverse_start: usize,
+    len: usize,
+    pattern_len: usize,
+}
+
+impl BidirectionalInterval {
+    /// The suffix array interval of the pattern in the text.
+    pub fn forward(&self) -> SuffixArrayInterval {
+        SuffixArrayInterval::new(self.forward_start, self.forward_start + self.len)
+    }
+
+    /// The suffix array interval of the reversed pattern in the reversed text.
+    pub fn reverse(&self) -> SuffixArrayInterval {
+        SuffixArrayInterval::new(self.reverse_start, self.reverse_start + self.len)
+    }
+
+    /// The number of occurrences of the pattern.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The length of the pattern that this interval belongs to.
+    pub fn pattern_len(&self) -> usize {
+        self.pattern_len
+    }
+}
+
+impl<I: SmallAlphabet> BidirectionalFmIndex<I> {
+    /// Construct the BWTs of the text and of the reversed text single-threaded and build the FM-indices with
+    /// the given sampling rate from them.
+    ///
+    /// # Panics
+    ///
+    /// If the sampling rate is 0.
+    pub fn new(text: &[I], sampling_rate: usize) -> Result<Self, LibsaisError> {
+        let reversed_text: Vec<_> = text.iter().rev().copied().collect();
+
+        Ok(Self {
+            forward: build_fm_index(text, sampling_rate)?,
+            reverse: build_fm_index(&reversed_text, sampling_rate)?,
+        })
+    }
+
+    /// Combine an FM-index of a text and an FM-index of the reversed text.
+    ///
+    /// # Panics
+    ///
+    /// If the lengths of the indices differ. It is not checked whether the second index actually belongs to
+    /// the reversed text. If not, the results of the queries are meaningless.
+    pub fn from_fm_indices(forward: FmIndex<I>, reverse: FmIndex<I>) -> Self {
+        assert_eq!(
+            forward.len(),
+            reverse.len(),
+            "The FM-indices of the text and the reversed text must have the same length"
+        );
+
+        Self { forward, reverse }
+    }
+
+    /// The FM-index of the text.
+    pub fn forward_index(&self) -> &FmIndex<I> {
+        &self.forward
+    }
+
+    /// The FM-index of the reversed text.
+    pub fn reverse_index(&self) -> &FmIndex<I> {
+        &self.reverse
+    }
+
+    /// The length of the indexed text.
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    /// The interval of the empty pattern.
+    pub fn root_interval(&self) -> BidirectionalInterval {
+        BidirectionalInterval {
+            forward_start: 0,
+            reverse_start: 0,
+            len: self.len(),
+            pattern_len: 0,
+        }
+    }
+
+    /// The interval of the pattern extended by `symbol` on the left.
+    pub fn extend_left(&self, interval: BidirectionalInterval, symbol: I) -> BidirectionalInterval {
+        let (forward_start, reverse_start, len) = extend(
+            &self.forward,
+            self.rows(interval.forward_start, interval),
+            self.rows(interval.reverse_start, interval).0,
+            symbol,
+        );
+
+        BidirectionalInterval {
+            forward_start,
+            reverse_start,
+            len,
+            pattern_len: interval.pattern_len + 1,
+        }
+    }
+
+    /// The interval of the pattern extended by `symbol` on the right.
+    pub fn extend_right(
+        &self,
+        interval: BidirectionalInterval,
+        symbol: I,
+    ) -> BidirectionalInterval {
+        let (reverse_start, forward_start, len) = extend(
+            &self.reverse,
+            self.rows(interval.reverse_start, interval),
+            self.rows(interval.forward_start, interval).0,
+            symbol,
+        );
+
+        BidirectionalInterval {
+            forward_start,
+            reverse_start,
+            len,
+            pattern_len: interval.pattern_len + 1,
+        }
+    }
+
+    /// The interval of `pattern`, found via backward search.
+    pub fn interval(&self, pattern: &[I]) -> BidirectionalInterval {
+        let mut interval = self.root_interval();
+
+        for &symbol in pattern.iter().rev() {
+            interval = self.extend_left(interval, symbol);
+        }
+
+        interval
+    }
+
+    /// The number of occurrences of `pattern` in the text.
+    pub fn count(&self, pattern: &[I]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    pub fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        self.locate_interval(self.interval(pattern))
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    pub fn locate_sorted(&self, pattern: &[I]) -> Vec<usize> {
+        let mut positions = self.locate(pattern);
+        positions.sort_unstable();
+        positions
+    }
+
+    /// The positions of the occurrences of the pattern of `interval` in the text, in suffix array order.
+    pub fn locate_interval(&self, interval: BidirectionalInterval) -> Vec<usize> {
+        interval
+            .forward()
+            .range()
+            .map(|suffix_array_index| self.forward.suffix_array_entry(suffix_array_index))
+            .collect()
+    }
+
+    // the rows of the BWT matrix of one side of the interval, the interval of the empty pattern
+    // additionally contains the sentinel row
+    fn rows(&self, start: usize, interval: BidirectionalInterval) -> (usize, usize) {
+        if interval.pattern_len == 0 {
+            (0, self.len() + 1)
+        } else {
+            (start + 1, start + 1 + interval.len)
+        }
+    }
+}
+
+// Extends the pattern of the interval with the given rows in index by symbol using backward search.
+// The start of the synchronized interval in the other index is shifted by the number of
+// occurrences of the pattern that are preceded by the sentinel or a smaller symbol.
+fn extend<I: SmallAlphabet>(
+    index: &FmIndex<I>,
+    (start_row, end_row): (usize, usize),
+    other_start_row: usize,
+    symbol: I,
+) -> (usize, usize, usize) {
+    let (new_start_row, new_end_row) = index.lf_interval(start_row, end_row, symbol);
+    let new_other_start_row = other_start_row + index.count_smaller(symbol, start_row, end_row);
+
+    // the new rows cannot contain the sentinel row, which is row 0
+    (
+        new_start_row - 1,
+        new_other_start_row - 1,
+        new_end_row - new_start_row,
+    )
+}
+
+fn build_fm_index<I: SmallAlphabet>(
+    text: &[I],
+    sampling_rate: usize,
+) -> Result<FmIndex<I>, LibsaisError> {
+    if text.len() <= LIBSAIS_I32_OUTPUT_MAXIMUM_SIZE {
+        let bwt = BwtConstruction::for_text(text)
+            .with_owned_temporary_array_buffer32()
+            .single_threaded()
+            .run()?;
+
+        Ok(FmIndex::new(&bwt, sampling_rate))
+    } else {
+        let bwt = BwtConstruction::for_text(text)
+            .with_owned_temporary_array_buffer64()
+            .single_threaded()
+            .run()?;
+
+        Ok(FmIndex::new(&bwt, sampling_rate))
+    }
+}
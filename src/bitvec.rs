@@ -0,0 +1,429 @@
+/*!
+ * Answer rank and select queries on a plain bit vector in constant or near-constant time.
+ *
+ * The [`BitVector`] is the building block of several data structures of this crate, such as the sampled suffix
+ * arrays of the [`FmIndex`] and the [`CompressedSuffixArray`] and the levels of the [`WaveletMatrix`]. It is
+ * useful for many other structures on the output of `libsais`, for example to mark the run heads of a BWT or the
+ * document boundaries of a generalized suffix array.
+ *
+ * * [`BitVector::rank1`] and [`BitVector::rank0`] need constant time. The number of ones is stored before every
+ *   block of 512 bits in a `usize` and before every 64-bit word relative to its block in a `u16`. This needs 37.5%
+ *   additional memory.
+ * * [`BitVector::select1`] and [`BitVector::select0`] store the word of every 512th one and zero. A query binary
+ *   searches the rank information between two of these samples and then scans a single word, which needs
+ *   near-constant time unless the bits are distributed very unevenly.
+ *
+ * Like in the rest of this crate, all positions and ranks are `usize` values and ranks count the bits in the
+ * half-open range before a position. Values of an [`OutputElement`](crate::OutputElement) array, such as suffix
+ * array entries, can be converted with `to_usize`.
+ *
+ * ```
+ * use libsais::bitvec::BitVector;
+ *
+ * let bitvec = BitVector::from_ones(10, [1, 4, 5, 9]);
+ *
+ * assert_eq!(bitvec.rank1(5), 2);
+ * assert_eq!(bitvec.rank0(5), 3);
+ * assert_eq!(bitvec.select1(2), 5);
+ * assert_eq!(bitvec.select0(2), 3);
+ *
+ * let bytes = bitvec.to_bytes();
+ * assert_eq!(BitVector::from_bytes(&bytes), Ok(bitvec));
+ * ```
+ *
+ * # Serialization
+ *
+ * [`BitVector::to_bytes`] stores the length as a little-endian `u64`, followed by the bits as little-endian `u64`
+ * words, where bit `i` is bit `i % 64` of word `i / 64`. The rank and select information is not stored, it is
+ * recomputed by [`BitVector::from_bytes`] in linear time.
+ *
+ * [`FmIndex`]: crate::fm_index::FmIndex
+ * [`CompressedSuffixArray`]: crate::csa::CompressedSuffixArray
+ * [`WaveletMatrix`]: crate::wavelet_matrix::WaveletMatrix
+ */
+
+use std::fmt::{Debug, Display};
+
+const WORDS_PER_BLOCK: usize = 8;
+
+// every SELECT_SAMPLE_RATE-th one and zero is sampled for select queries
+const SELECT_SAMPLE_RATE: usize = 512;
+
+/// A plain bit vector with rank and select support.
+///
+/// See [`bitvec`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVector {
+    words: Vec<u64>,
+    // the number of ones before every block and before every word relative to its block,
+    // both with an additional entry for the end
+    block_ranks: Vec<usize>,
+    word_ranks: Vec<u16>,
+    // the words of every SELECT_SAMPLE_RATE-th one and zero
+    select1_samples: Vec<usize>,
+    select0_samples: Vec<usize>,
+    len: usize,
+}
+
+impl BitVector {
+    /// Create a bit vector of length `len` in which exactly the bits at the positions of `ones` are set.
+    ///
+    /// # Panics
+    ///
+    /// If a position is out of bounds.
+    pub fn from_ones(len: usize, ones: impl IntoIterator<Item = usize>) -> Self {
+        let mut words = vec![0u64; len.div_ceil(64)];
+
+        for i in ones {
+            assert!(i < len, "Bit index out of bounds");
+            words[i / 64] |= 1 << (i % 64);
+        }
+
+        Self::from_words(words, len)
+    }
+
+    // the bits after len in the last word must not be set
+    fn from_words(words: Vec<u64>, len: usize) -> Self {
+        let mut block_ranks = Vec::with_capacity(words.len() / WORDS_PER_BLOCK + 1);
+        let mut word_ranks = Vec::with_capacity(words.len() + 1);
+        let mut select1_samples = Vec::new();
+        let mut select0_samples = Vec::new();
+        let mut rank = 0;
+
+        for word_index in 0..=words.len() {
+            if word_index % WORDS_PER_BLOCK == 0 {
+                block_ranks.push(rank);
+            }
+
+            word_ranks.push((rank - block_ranks[word_index / WORDS_PER_BLOCK]) as u16);
+
+            let Some(&word) = words.get(word_index) else {
+                break;
+            };
+
+            let ones = word.count_ones() as usize;
+            let zeros = (64 * (word_index + 1)).min(len) - 64 * word_index - ones;
+            let zero_rank = 64 * word_index - rank;
+
+            // the samples of the ones and zeros in this word
+            select1_samples.extend(
+                std::iter::repeat_n(word_index, (rank + ones).div_ceil(SELECT_SAMPLE_RATE))
+                    .skip(select1_samples.len()),
+            );
+            select0_samples.extend(
+                std::iter::repeat_n(word_index, (zero_rank + zeros).div_ceil(SELECT_SAMPLE_RATE))
+                    .skip(select0_samples.len()),
+            );
+
+            rank += ones;
+        }
+
+        Self {
+            words,
+            block_ranks,
+            word_ranks,
+            select1_samples,
+            select0_samples,
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.ones_before_word(self.words.len())
+    }
+
+    /// The number of unset bits.
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// # Panics
+    ///
+    /// If the index is out of bounds.
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "Bit index out of bounds");
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// The number of ones in `[0, i)`.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is greater than the length.
+    pub fn rank1(&self, i: usize) -> usize {
+        assert!(i <= self.len, "Rank index out of bounds");
+
+        let word_index = i / 64;
+        let bit_index = i % 64;
+
+        if bit_index == 0 {
+            self.ones_before_word(word_index)
+        } else {
+            let mask = (1u64 << bit_index) - 1;
+            self.ones_before_word(word_index)
+                + (self.words[word_index] & mask).count_ones() as usize
+        }
+    }
+
+    /// The number of zeros in `[0, i)`.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is greater than the length.
+    pub fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// The position of the one with the given rank, starting from 0.
+    ///
+    /// # Panics
+    ///
+    /// If the rank is not smaller than the number of ones.
+    pub fn select1(&self, rank: usize) -> usize {
+        assert!(rank < self.count_ones(), "Select rank out of bounds");
+
+        let word_index = self.select_word(rank, &self.select1_samples, |word_index| {
+            self.ones_before_word(word_index)
+        });
+
+        word_index * 64
+            + select_in_word(
+                self.words[word_index],
+                rank - self.ones_before_word(word_index),
+            )
+    }
+
+    /// The position of the zero with the given rank, starting from 0.
+    ///
+    /// # Panics
+    ///
+    /// If the rank is not smaller than the number of zeros.
+    pub fn select0(&self, rank: usize) -> usize {
+        assert!(rank < self.count_zeros(), "Select rank out of bounds");
+
+        let zeros_before_word =
+            |word_index: usize| word_index * 64 - self.ones_before_word(word_index);
+        let word_index = self.select_word(rank, &self.select0_samples, zeros_before_word);
+
+        // the unused bits of the last word are zeros after all zeros of the bit vector
+        word_index * 64
+            + select_in_word(
+                !self.words[word_index],
+                rank - zeros_before_word(word_index),
+            )
+    }
+
+    /// Serialize the bit vector. See [`bitvec`](self#serialization) for the format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 * (self.words.len() + 1));
+        bytes.extend_from_slice(&(self.len as u64).to_le_bytes());
+
+        for word in &self.words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Deserialize a bit vector that was serialized using [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
+        let (len_bytes, word_bytes) =
+            bytes
+                .split_first_chunk::<8>()
+                .ok_or(FromBytesError::UnexpectedLength {
+                    expected: 8,
+                    actual: bytes.len(),
+                })?;
+
+        let len = usize::try_from(u64::from_le_bytes(*len_bytes))
+            .map_err(|_| FromBytesError::LengthTooLarge)?;
+        let expected = len
+            .div_ceil(64)
+            .checked_mul(8)
+            .and_then(|num_word_bytes| num_word_bytes.checked_add(8))
+            .ok_or(FromBytesError::LengthTooLarge)?;
+
+        if bytes.len() != expected {
+            return Err(FromBytesError::UnexpectedLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        let words: Vec<_> = word_bytes
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        if len % 64 != 0 && words.last().is_some_and(|&word| word >> (len % 64) != 0) {
+            return Err(FromBytesError::BitAfterEnd);
+        }
+
+        Ok(Self::from_words(words, len))
+    }
+
+    fn ones_before_word(&self, word_index: usize) -> usize {
+        self.block_ranks[word_index / WORDS_PER_BLOCK] + self.word_ranks[word_index] as usize
+    }
+
+    // the last word with at most rank ones (or zeros) before it, between the neighboring samples
+    fn select_word(
+        &self,
+        rank: usize,
+        samples: &[usize],
+        bits_before_word: impl Fn(usize) -> usize,
+    ) -> usize {
+        let sample_index = rank / SELECT_SAMPLE_RATE;
+        let mut left = samples[sample_index];
+        let mut right = samples
+            .get(sample_index + 1)
+            .map_or(self.words.len(), |&word_index| word_index + 1);
+
+        while right - left > 1 {
+            let middle = (left + right) / 2;
+
+            if bits_before_word(middle) <= rank {
+                left = middle;
+            } else {
+                right = middle;
+            }
+        }
+
+        left
+    }
+}
+
+impl FromIterator<bool> for BitVector {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let mut words = Vec::new();
+        let mut len = 0;
+
+        for bit in iter {
+            if len % 64 == 0 {
+                words.push(0);
+            }
+
+            *words.last_mut().unwrap() |= (bit as u64) << (len % 64);
+            len += 1;
+        }
+
+        Self::from_words(words, len)
+    }
+}
+
+/// The error type for deserializing a [`BitVector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromBytesError {
+    /// The number of bytes does not match the length that is stored in the first 8 bytes.
+    UnexpectedLength { expected: usize, actual: usize },
+    /// The stored length does not fit into the address space of this platform.
+    LengthTooLarge,
+    /// A bit after the end of the bit vector is set in the last word.
+    BitAfterEnd,
+}
+
+impl Display for FromBytesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <FromBytesError as Debug>::fmt(self, f)
+    }
+}
+
+impl std::error::Error for FromBytesError {}
+
+// position of the set bit with the given rank in the word
+fn select_in_word(mut word: u64, rank: usize) -> usize {
+    for _ in 0..rank {
+        // clear the lowest set bit
+        word &= word - 1;
+    }
+
+    word.trailing_zeros() as usize
+}
+
+// Elias-Fano encoding of a non-decreasing sequence of integers. The lower bits of every value are
+// stored in a packed array and the upper bits are stored in unary in a bit vector.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct EliasFano {
+    upper_bits: BitVector,
+    lower_bits: Vec<u64>,
+    lower_width: usize,
+    len: usize,
+}
+
+impl EliasFano {
+    // values must be non-decreasing and smaller than universe
+    pub(crate) fn new(values: &[usize], universe: usize) -> Self {
+        let len = values.len();
+        let lower_width = if len == 0 || universe <= len {
+            0
+        } else {
+            (universe / len).ilog2() as usize
+        };
+
+        let upper_bits = BitVector::from_ones(
+            (universe >> lower_width) + len + 1,
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| (value >> lower_width) + i),
+        );
+
+        let mut lower_bits = vec![0u64; (len * lower_width).div_ceil(64)];
+
+        if lower_width > 0 {
+            let mask = (1u64 << lower_width) - 1;
+
+            for (i, &value) in values.iter().enumerate() {
+                debug_assert!(i == 0 || values[i - 1] <= value);
+
+                let lower = value as u64 & mask;
+                let bit_offset = i * lower_width;
+                let (word_index, bit_index) = (bit_offset / 64, bit_offset % 64);
+
+                lower_bits[word_index] |= lower << bit_index;
+
+                if bit_index + lower_width > 64 {
+                    lower_bits[word_index + 1] |= lower >> (64 - bit_index);
+                }
+            }
+        }
+
+        Self {
+            upper_bits,
+            lower_bits,
+            lower_width,
+            len,
+        }
+    }
+
+    pub(crate) fn get(&self, i: usize) -> usize {
+        assert!(i < self.len, "Elias-Fano index out of bounds");
+
+        let upper = self.upper_bits.select1(i) - i;
+
+        if self.lower_width == 0 {
+            return upper;
+        }
+
+        let bit_offset = i * self.lower_width;
+        let (word_index, bit_index) = (bit_offset / 64, bit_offset % 64);
+        let mut lower = self.lower_bits[word_index] >> bit_index;
+
+        if bit_index + self.lower_width > 64 {
+            lower |= self.lower_bits[word_index + 1] << (64 - bit_index);
+        }
+
+        let mask = (1u64 << self.lower_width) - 1;
+
+        (upper << self.lower_width) | (lower & mask) as usize
+    }
+}
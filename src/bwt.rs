@@ -57,6 +57,8 @@
  * The read-only return type of [`BwtConstruction::run`] bundles the BWT with either the primary index or the
  * auxiliary indices and their sampling rate. It is generic over whether an owned or borrowed output buffer is used.
  * The object can be destructured into parts or used to safely reverse the BWT and obtain the text again.
+ * With auxiliary indices, [`BwtWithAuxIndices::extractor`] builds the rank information once and then recovers
+ * substrings of the text without reversing the whole BWT.
  *
  * An example of constructing and reversing the BWT can be found
  * [here](https://github.com/feldroop/libsais-rs/blob/master/examples/to_the_bwt_and_back.rs).
@@ -69,11 +71,14 @@
 use either::Either;
 use num_traits::NumCast;
 
+use std::borrow::Cow;
 use std::marker::PhantomData;
+use std::ops::Range;
 
 use crate::{
     IntoSaisResult, LibsaisError, OutputElement, SmallAlphabet, ThreadCount,
     context::Context,
+    fm_index::{self, LfMapping},
     generics_dispatch::{LibsaisFunctionsSmallAlphabet, SmallAlphabetFunctionsDispatch},
     owned_or_borrowed::OwnedOrBorrowed,
     suffix_array::{self, ExtraSpace},
@@ -722,6 +727,13 @@ impl<'a, 'b, I: SmallAlphabet, O: OutputElement, BwtB: BufferMode, AuxB: BufferM
         &self.aux_indices.buffer
     }
 
+    /// The primary index, which is the first auxiliary index.
+    pub fn primary_index(&self) -> usize {
+        self.aux_indices()
+            .first()
+            .map_or(0, |&index| <usize as NumCast>::from(index).unwrap())
+    }
+
     pub fn aux_indices_sampling_rate(&self) -> AuxIndicesSamplingRate<O> {
         self.aux_indices_sampling_rate
     }
@@ -759,6 +771,18 @@ impl<'a, 'b, I: SmallAlphabet, O: OutputElement, BwtB: BufferMode, AuxB: BufferM
         }
     }
 
+    /// Build the rank information for the LF mapping once, to extract substrings of the text without reversing
+    /// the whole BWT.
+    ///
+    /// The extractor borrows the BWT and the auxiliary indices and needs `O(n)` time to build.
+    pub fn extractor(&self) -> BwtExtractor<'_, I, O> {
+        BwtExtractor {
+            lf_mapping: LfMapping::new(Cow::Borrowed(self.bwt()), self.primary_index()),
+            aux_indices: self.aux_indices(),
+            sampling_rate: self.aux_indices_sampling_rate.value(),
+        }
+    }
+
     pub fn unbwt(self) -> UnBwt<'b, 'a, 'static, I, O, BwtB, OwnedBuffer, Undecided> {
         UnBwt {
             bwt: Some(self.bwt),
@@ -775,6 +799,56 @@ impl<'a, 'b, I: SmallAlphabet, O: OutputElement, BwtB: BufferMode, AuxB: BufferM
     }
 }
 
+/// Extracts substrings of the text from a BWT with auxiliary indices.
+///
+/// See [`BwtWithAuxIndices::extractor`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BwtExtractor<'e, I: SmallAlphabet, O: OutputElement> {
+    lf_mapping: LfMapping<'e, I>,
+    aux_indices: &'e [O],
+    sampling_rate: usize,
+}
+
+impl<I: SmallAlphabet, O: OutputElement> BwtExtractor<'_, I, O> {
+    /// Recover the substring `text[range]` without reversing the whole BWT.
+    ///
+    /// The LF mapping is applied starting from the auxiliary index of the next sampled text position after the
+    /// range, the same way `libsais` uses the auxiliary indices as restart points for the reversal. At most
+    /// `r + range.len()` LF steps are needed, where `r` is the sampling rate.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    pub fn extract(&self, range: Range<usize>) -> Vec<I> {
+        let len = self.lf_mapping.bwt().len();
+
+        assert!(
+            range.start <= range.end && range.end <= len,
+            "Range out of bounds"
+        );
+
+        let sample_index = range.end.div_ceil(self.sampling_rate);
+        let sampled_text_position = sample_index * self.sampling_rate;
+
+        // AUX[i] == k => SUF[k - 1] = i * r, and the sentinel row 0 belongs to the end of the text
+        let (text_position, row) = match self.aux_indices.get(sample_index) {
+            Some(&row) if sampled_text_position < len => (
+                sampled_text_position,
+                <usize as NumCast>::from(row).unwrap(),
+            ),
+            _ => (len, 0),
+        };
+
+        fm_index::extract_with_lf(
+            range,
+            text_position,
+            row,
+            |row| self.lf_mapping.last_column_symbol(row),
+            |row| self.lf_mapping.lf(row),
+        )
+    }
+}
+
 /// The sampling rate for auxiliary indices of the BWT construction
 ///
 /// See [`bwt`](self#primary-index-and-auxiliary-indices) for details.
@@ -0,0 +1,291 @@
+/*!
+ * Find the longest substrings that are common to at least `k` of a set of texts, for every `k`.
+ *
+ * [`SuffixArrayWithLcpAndPlcp::longest_common_substrings`] takes the generalized suffix array of a set of texts
+ * (see [`suffix_array`](super::suffix_array#generalized-suffix-array)) with its LCP array. It determines the number
+ * of distinct texts (documents) in every LCP interval with a single bottom-up traversal. When the same document
+ * occurs twice in a row in the suffix array, the lowest common LCP interval of the two suffixes receives a
+ * correction, such that every document is only counted once. The lowest common interval is found on the stack of
+ * the traversal by binary search. Overall, this needs `O(n log n)` time, where `n` is the length of the
+ * concatenated texts, plus the time to report the results.
+ *
+ * For every `k` from 1 to the number of documents, the longest substrings that occur in at least `k` documents are
+ * reported as [`CommonSubstring`]s, together with the documents in which they occur.
+ *
+ * ```
+ * use libsais::SuffixArrayConstruction;
+ *
+ * let text = b"abcdef\0xbcdey\0cdez\0".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .generalized_suffix_array()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let common_substrings = res.longest_common_substrings(text);
+ *
+ * // "bcde" occurs in two documents, "cde" in all three
+ * let in_two = &common_substrings[1];
+ * assert_eq!((in_two.len(), in_two[0].len()), (1, 4));
+ * assert_eq!(in_two[0].documents(), &[0, 1]);
+ *
+ * let in_three = &common_substrings[2];
+ * assert_eq!((in_three[0].len(), in_three[0].documents()), (3, [0, 1, 2].as_slice()));
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The documents are numbered in the order in which they appear in the concatenated text. The common substrings
+ * never contain a separator, because the LCP values of generalized suffix arrays stop at the separators (see
+ * [`lcp`](super::lcp#generalized-suffix-array-support)).
+ */
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement, lcp::SuffixArrayWithLcpAndPlcp, search::SuffixArrayInterval,
+    typestate::BufferMode,
+};
+
+/// A substring that is common to several documents of a generalized suffix array.
+///
+/// See [`common_substrings`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommonSubstring {
+    len: usize,
+    interval: SuffixArrayInterval,
+    documents: Vec<usize>,
+}
+
+impl CommonSubstring {
+    /// The length of the common substring.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The suffix array interval of the occurrences of the common substring.
+    pub fn interval(&self) -> SuffixArrayInterval {
+        self.interval
+    }
+
+    /// The documents that contain the common substring, in ascending order.
+    pub fn documents(&self) -> &[usize] {
+        &self.documents
+    }
+}
+
+// an LCP interval during the bottom-up traversal
+#[derive(Debug)]
+struct OpenInterval {
+    lcp: isize,
+    start: usize,
+    // the number of pairs of consecutive suffixes of the same document in the subtree
+    corrections: usize,
+}
+
+// a substring that could be reported, with the number of documents that contain it
+#[derive(Debug)]
+struct Candidate {
+    len: usize,
+    interval: SuffixArrayInterval,
+    num_documents: usize,
+}
+
+impl<'l, 'p, 's, O: OutputElement, SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>
+    SuffixArrayWithLcpAndPlcp<'l, 'p, 's, O, SaB, LcpB, PlcpB>
+{
+    /// The longest substrings that occur in at least `k` documents, for every `k`.
+    ///
+    /// The entry at index `k - 1` contains the substrings for `k`, ordered by their suffix array intervals. It is
+    /// empty if there is no non-empty substring that occurs in `k` documents. `text` must be the concatenated text
+    /// for which the generalized suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the suffix array is not a generalized suffix array or if the text does not have the same length as the
+    /// suffix array.
+    pub fn longest_common_substrings<I: InputElement>(
+        &self,
+        text: &[I],
+    ) -> Vec<Vec<CommonSubstring>> {
+        assert!(
+            self.is_generalized_suffix_array(),
+            "Common substrings need a generalized suffix array"
+        );
+        assert_eq!(
+            text.len(),
+            self.suffix_array().len(),
+            "The text must have the same length as the suffix array"
+        );
+
+        // the document of every text position, the separator belongs to the document it ends
+        let mut documents = Vec::with_capacity(text.len());
+        let mut document_ends = Vec::new();
+
+        for (position, &symbol) in text.iter().enumerate() {
+            documents.push(document_ends.len());
+
+            if symbol == I::zero() {
+                document_ends.push(position);
+            }
+        }
+
+        let candidates = self.common_substring_candidates(&documents, &document_ends);
+
+        // the longest length for every number of documents, then for at least k documents
+        let mut longest_lens = vec![0; document_ends.len()];
+        for candidate in &candidates {
+            let longest_len = &mut longest_lens[candidate.num_documents - 1];
+            *longest_len = (*longest_len).max(candidate.len);
+        }
+        for k in (1..longest_lens.len()).rev() {
+            longest_lens[k - 1] = longest_lens[k - 1].max(longest_lens[k]);
+        }
+
+        let suffix = |suffix_array_index: usize| {
+            <usize as NumCast>::from(self.suffix_array()[suffix_array_index]).unwrap()
+        };
+        let mut common_substrings = vec![Vec::new(); document_ends.len()];
+
+        for candidate in candidates {
+            // longest_lens is non-increasing, so the candidate is reported for a range of k
+            let first_k = longest_lens.partition_point(|&len| len > candidate.len);
+            let end_k = longest_lens[..candidate.num_documents]
+                .partition_point(|&len| len >= candidate.len);
+
+            if first_k >= end_k {
+                continue;
+            }
+
+            let mut candidate_documents: Vec<_> = candidate
+                .interval
+                .range()
+                .map(|suffix_array_index| documents[suffix(suffix_array_index)])
+                .collect();
+            candidate_documents.sort_unstable();
+            candidate_documents.dedup();
+
+            let common_substring = CommonSubstring {
+                len: candidate.len,
+                interval: candidate.interval,
+                documents: candidate_documents,
+            };
+
+            for substrings in &mut common_substrings[first_k..end_k] {
+                substrings.push(common_substring.clone());
+            }
+        }
+
+        for substrings in &mut common_substrings {
+            substrings.sort_unstable_by_key(|common_substring| common_substring.interval);
+        }
+
+        common_substrings
+    }
+
+    // all LCP intervals with their number of documents and the leaves that are longer than their parents
+    fn common_substring_candidates(
+        &self,
+        documents: &[usize],
+        document_ends: &[usize],
+    ) -> Vec<Candidate> {
+        let len = self.suffix_array().len();
+        let suffix = |suffix_array_index: usize| {
+            <usize as NumCast>::from(self.suffix_array()[suffix_array_index]).unwrap()
+        };
+        let lcp_value = |i: usize| {
+            if i == 0 || i == len {
+                0
+            } else {
+                <usize as NumCast>::from(self.lcp()[i]).unwrap()
+            }
+        };
+
+        let mut candidates = Vec::new();
+
+        // leaves occur in a single document, the suffixes of separators are empty
+        for suffix_array_index in 0..len {
+            let suffix = suffix(suffix_array_index);
+            let leaf_len = document_ends[documents[suffix]] - suffix;
+
+            if leaf_len > lcp_value(suffix_array_index).max(lcp_value(suffix_array_index + 1)) {
+                candidates.push(Candidate {
+                    len: leaf_len,
+                    interval: SuffixArrayInterval::new(suffix_array_index, suffix_array_index + 1),
+                    num_documents: 1,
+                });
+            }
+        }
+
+        let mut last_suffix_array_indices = vec![None; document_ends.len()];
+        let mut stack = vec![OpenInterval {
+            lcp: 0,
+            start: 0,
+            corrections: 0,
+        }];
+
+        for i in 1..=len {
+            // the correction for the leaf before i goes to the lowest interval that contains the
+            // previous leaf of the same document
+            let document = documents[suffix(i - 1)];
+
+            if let Some(previous) = last_suffix_array_indices[document] {
+                let lowest_common_interval =
+                    stack.partition_point(|interval| interval.start <= previous) - 1;
+                stack[lowest_common_interval].corrections += 1;
+            }
+
+            last_suffix_array_indices[document] = Some(i - 1);
+
+            let lcp = if i == len { -1 } else { lcp_value(i) as isize };
+            let mut start = i - 1;
+            let mut carried_corrections = 0;
+
+            while stack.last().is_some_and(|top| lcp < top.lcp) {
+                let interval = stack.pop().unwrap();
+                let num_documents = (i - interval.start) - interval.corrections;
+
+                if interval.lcp > 0 {
+                    candidates.push(Candidate {
+                        len: interval.lcp as usize,
+                        interval: SuffixArrayInterval::new(interval.start, i),
+                        num_documents,
+                    });
+                }
+
+                start = interval.start;
+                carried_corrections = interval.corrections;
+
+                // the corrections go to the parent, which might be the interval that is pushed next
+                if let Some(top) = stack.last_mut().filter(|top| lcp <= top.lcp) {
+                    top.corrections += carried_corrections;
+                    carried_corrections = 0;
+                }
+            }
+
+            if stack.last().is_some_and(|top| lcp > top.lcp) {
+                stack.push(OpenInterval {
+                    lcp,
+                    start,
+                    corrections: carried_corrections,
+                });
+            }
+        }
+
+        candidates
+    }
+}
@@ -0,0 +1,332 @@
+/*!
+ * Store a queryable suffix array in a fraction of its memory using a [compressed suffix array] (CSA).
+ *
+ * The [`CompressedSuffixArray`] is based on the `Ψ` function, which maps the position of a suffix in the suffix
+ * array to the position of the suffix that is one symbol shorter, i.e. `Ψ[i] = ISA[SA[i] + 1]`. Inside of the range
+ * of suffixes that start with the same symbol, `Ψ` is increasing. After adding an offset for every such range, the
+ * whole `Ψ` function is an increasing sequence, which is stored using Elias-Fano encoding in about `2 + log σ` bits
+ * per entry, where `σ` is the number of distinct symbols of the text. The first symbols of the suffixes are
+ * implicitly encoded by the offsets, so the text is not needed after the construction.
+ *
+ * Every `s`-th text position is sampled, both for the suffix array and the inverse suffix array. Accessing an entry
+ * of either array needs at most `s` evaluations of `Ψ`. Extracting a substring of length `l` needs at most
+ * `s + l` evaluations and patterns can be found by binary search.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, csa::CompressedSuffixArray};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer64()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let csa = CompressedSuffixArray::new(&res, 4);
+ *
+ * assert_eq!(csa.suffix_array_entry(2), 0);
+ * assert_eq!(csa.inverse_suffix_array_entry(0), 2);
+ * assert_eq!(csa.extract(7..11), b"abra");
+ * assert_eq!(csa.locate_sorted(b"abra"), vec![0, 7]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * All functions of the [`CompressedSuffixArray`] use the convention of `libsais`, i.e. the suffix array does not
+ * contain an entry for the sentinel. Consequently, [`CompressedSuffixArray::psi`] is not defined for the suffix array
+ * entry of the last text position.
+ *
+ * For generalized suffix arrays, every zero separator is treated as a unique symbol. This increases the size of
+ * the index slightly for every text in the set.
+ *
+ * [compressed suffix array]: https://doi.org/10.1016/S0196-6774(02)00280-8
+ */
+
+use std::{cmp::Ordering, ops::Range};
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement,
+    bitvec::{BitVector, EliasFano},
+    search::SuffixArrayInterval,
+    suffix_array::SuffixArrayWithText,
+    typestate::BufferMode,
+};
+
+/// A compressed suffix array based on the `Ψ` function.
+///
+/// See [`csa`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompressedSuffixArray<I: InputElement> {
+    text_len: usize,
+    // Ψ for the rows of the suffix array with the sentinel suffix at row 0. Every value is
+    // increased by the bucket index of its row times the number of rows.
+    psi: EliasFano,
+    // the first symbol of every bucket, the first bucket belongs to the sentinel
+    bucket_symbols: Vec<I>,
+    sampled_rows: BitVector,
+    suffix_array_samples: Vec<usize>,
+    // rows of every sampling_rate-th text position
+    inverse_suffix_array_samples: Vec<usize>,
+    sampling_rate: usize,
+}
+
+impl<I: InputElement> CompressedSuffixArray<I> {
+    /// Build the compressed suffix array and sample every `sampling_rate`-th text position.
+    ///
+    /// # Panics
+    ///
+    /// If the sampling rate is 0.
+    pub fn new<O: OutputElement, B: BufferMode>(
+        suffix_array_with_text: &SuffixArrayWithText<'_, '_, I, O, B>,
+        sampling_rate: usize,
+    ) -> Self {
+        assert!(sampling_rate > 0, "Sampling rate cannot be 0");
+
+        let text = suffix_array_with_text.text();
+        let suffix_array = suffix_array_with_text.suffix_array();
+        let is_generalized_suffix_array = suffix_array_with_text.is_generalized_suffix_array();
+
+        let text_len = text.len();
+        let num_rows = text_len + 1;
+
+        // row 0 belongs to the sentinel suffix, which starts at text position text_len
+        let text_positions: Vec<usize> = std::iter::once(text_len)
+            .chain(
+                suffix_array
+                    .iter()
+                    .map(|&suffix| <usize as NumCast>::from(suffix).unwrap()),
+            )
+            .collect();
+
+        let mut rows = vec![0; num_rows];
+        for (row, &text_position) in text_positions.iter().enumerate() {
+            rows[text_position] = row;
+        }
+
+        let mut bucket_symbols = vec![I::zero()];
+        let mut psi_values = Vec::with_capacity(num_rows);
+        psi_values.push(rows[0]);
+
+        for row in 1..num_rows {
+            let symbol = text[text_positions[row]];
+
+            let is_new_bucket = row == 1
+                || symbol != *bucket_symbols.last().unwrap()
+                || (is_generalized_suffix_array && symbol == I::zero());
+
+            if is_new_bucket {
+                bucket_symbols.push(symbol);
+            }
+
+            let bucket = bucket_symbols.len() - 1;
+            psi_values.push(bucket * num_rows + rows[text_positions[row] + 1]);
+        }
+
+        let psi = EliasFano::new(&psi_values, bucket_symbols.len() * num_rows);
+
+        let sampled_rows = BitVector::from_ones(
+            num_rows,
+            (0..num_rows).filter(|&row| is_sampled(text_positions[row], text_len, sampling_rate)),
+        );
+        let suffix_array_samples = text_positions
+            .iter()
+            .copied()
+            .filter(|&text_position| is_sampled(text_position, text_len, sampling_rate))
+            .collect();
+        let inverse_suffix_array_samples = rows.iter().copied().step_by(sampling_rate).collect();
+
+        Self {
+            text_len,
+            psi,
+            bucket_symbols,
+            sampled_rows,
+            suffix_array_samples,
+            inverse_suffix_array_samples,
+            sampling_rate,
+        }
+    }
+
+    /// The length of the text.
+    pub fn len(&self) -> usize {
+        self.text_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text_len == 0
+    }
+
+    pub fn sampling_rate(&self) -> usize {
+        self.sampling_rate
+    }
+
+    /// The position in the suffix array of the suffix that is one symbol shorter than the suffix at
+    /// `suffix_array_index`. It is `None` for the suffix of length 1.
+    ///
+    /// # Panics
+    ///
+    /// If the index is out of bounds.
+    pub fn psi(&self, suffix_array_index: usize) -> Option<usize> {
+        assert!(
+            suffix_array_index < self.text_len,
+            "Suffix array index out of bounds"
+        );
+
+        self.psi_row(suffix_array_index + 1).checked_sub(1)
+    }
+
+    /// The entry of the suffix array at `suffix_array_index`.
+    ///
+    /// # Panics
+    ///
+    /// If the index is out of bounds.
+    pub fn suffix_array_entry(&self, suffix_array_index: usize) -> usize {
+        assert!(
+            suffix_array_index < self.text_len,
+            "Suffix array index out of bounds"
+        );
+
+        let mut row = suffix_array_index + 1;
+        let mut num_steps = 0;
+
+        while !self.sampled_rows.get(row) {
+            row = self.psi_row(row);
+            num_steps += 1;
+        }
+
+        self.suffix_array_samples[self.sampled_rows.rank1(row)] - num_steps
+    }
+
+    /// The position of the suffix starting at `text_position` in the suffix array.
+    ///
+    /// # Panics
+    ///
+    /// If the text position is out of bounds.
+    pub fn inverse_suffix_array_entry(&self, text_position: usize) -> usize {
+        assert!(text_position < self.text_len, "Text position out of bounds");
+
+        self.row_of_text_position(text_position) - 1
+    }
+
+    /// The substring of the text in the given range.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    pub fn extract(&self, range: Range<usize>) -> Vec<I> {
+        assert!(
+            range.start <= range.end && range.end <= self.text_len,
+            "Range out of bounds"
+        );
+
+        if range.is_empty() {
+            return Vec::new();
+        }
+
+        let mut row = self.row_of_text_position(range.start);
+
+        range
+            .map(|_| {
+                let (symbol, next_row) = self.first_symbol_and_psi(row);
+                row = next_row;
+                symbol.unwrap()
+            })
+            .collect()
+    }
+
+    /// The suffix array interval of all suffixes that start with `pattern`, found via binary search.
+    pub fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        let compare = |suffix_array_index: usize| {
+            self.compare_suffix_with_pattern(suffix_array_index + 1, pattern)
+        };
+
+        let start = self
+            .partition_point(|suffix_array_index| compare(suffix_array_index) == Ordering::Less);
+        let end = self
+            .partition_point(|suffix_array_index| compare(suffix_array_index) != Ordering::Greater);
+
+        SuffixArrayInterval::new(start, end)
+    }
+
+    /// The number of occurrences of `pattern` in the text.
+    pub fn count(&self, pattern: &[I]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    pub fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        self.interval(pattern)
+            .range()
+            .map(|suffix_array_index| self.suffix_array_entry(suffix_array_index))
+            .collect()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    pub fn locate_sorted(&self, pattern: &[I]) -> Vec<usize> {
+        let mut positions = self.locate(pattern);
+        positions.sort_unstable();
+        positions
+    }
+
+    // binary search over the suffix array indices
+    fn partition_point(&self, predicate: impl Fn(usize) -> bool) -> usize {
+        let (mut left, mut right) = (0, self.text_len);
+
+        while left < right {
+            let middle = (left + right) / 2;
+
+            if predicate(middle) {
+                left = middle + 1;
+            } else {
+                right = middle;
+            }
+        }
+
+        left
+    }
+
+    fn row_of_text_position(&self, text_position: usize) -> usize {
+        let sample_index = text_position / self.sampling_rate;
+        let mut row = self.inverse_suffix_array_samples[sample_index];
+
+        for _ in sample_index * self.sampling_rate..text_position {
+            row = self.psi_row(row);
+        }
+
+        row
+    }
+
+    fn psi_row(&self, row: usize) -> usize {
+        self.psi.get(row) % (self.text_len + 1)
+    }
+
+    // None for the sentinel
+    fn first_symbol_and_psi(&self, row: usize) -> (Option<I>, usize) {
+        let value = self.psi.get(row);
+        let bucket = value / (self.text_len + 1);
+        let symbol = (bucket > 0).then(|| self.bucket_symbols[bucket]);
+
+        (symbol, value % (self.text_len + 1))
+    }
+
+    // compares the prefix of the suffix at row that has the length of the pattern to the pattern
+    fn compare_suffix_with_pattern(&self, mut row: usize, pattern: &[I]) -> Ordering {
+        for &pattern_symbol in pattern {
+            let (symbol, next_row) = self.first_symbol_and_psi(row);
+
+            match symbol {
+                None => return Ordering::Less,
+                Some(symbol) if symbol != pattern_symbol => return symbol.cmp(&pattern_symbol),
+                _ => row = next_row,
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+fn is_sampled(text_position: usize, text_len: usize, sampling_rate: usize) -> bool {
+    text_position == text_len || text_position.is_multiple_of(sampling_rate)
+}
@@ -0,0 +1,144 @@
+/*!
+ * Count the distinct substrings of a text, in total and for every length.
+ *
+ * Every distinct substring is a prefix of some suffix. In the suffix array, the prefixes of the suffix at index
+ * `i` that are longer than `LCP[i]` do not occur in any lexicographically smaller suffix, so each of them is
+ * counted exactly once. Therefore, [`SuffixArrayWithLcpAndPlcp::num_distinct_substrings`] and
+ * [`SuffixArrayWithLcpAndPlcp::distinct_substring_profile`] need `O(n)` time plus, for the profile, the requested
+ * maximum length.
+ *
+ * The profile contains the number of distinct substrings of every length up to a bound. For a DNA text, the entry
+ * of length `k` is the number of distinct `k`-mers, which is used for example to estimate genome sizes and to
+ * choose `k` for de Bruijn graph assemblers.
+ *
+ * ```
+ * use libsais::SuffixArrayConstruction;
+ *
+ * let text = b"abab".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * // a, b, ab, ba, aba, bab, abab
+ * assert_eq!(res.num_distinct_substrings(text), 7);
+ * assert_eq!(res.distinct_substring_profile(text, 5), vec![2, 2, 2, 1, 0]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The empty substring is not counted. For generalized suffix arrays, substrings that contain a zero separator are
+ * not counted, i.e. only the distinct substrings of the individual texts are counted. A substring that occurs in
+ * several of the texts is counted once. This relies on the LCP values of generalized suffix arrays stopping at the
+ * separators (see [`lcp`](super::lcp#generalized-suffix-array-support)).
+ */
+
+use num_traits::NumCast;
+
+use crate::{InputElement, OutputElement, lcp::SuffixArrayWithLcpAndPlcp, typestate::BufferMode};
+
+impl<'l, 'p, 's, O: OutputElement, SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>
+    SuffixArrayWithLcpAndPlcp<'l, 'p, 's, O, SaB, LcpB, PlcpB>
+{
+    /// The number of distinct non-empty substrings of the text.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the text does not have the same length as the suffix array.
+    pub fn num_distinct_substrings<I: InputElement>(&self, text: &[I]) -> usize {
+        let mut num_distinct_substrings = 0;
+
+        self.for_each_new_prefix_range(text, |lcp, suffix_len| {
+            num_distinct_substrings += suffix_len - lcp;
+        });
+
+        num_distinct_substrings
+    }
+
+    /// The number of distinct substrings of every length from 1 to `max_len`.
+    ///
+    /// The entry at index `len - 1` is the number of distinct substrings of length `len`.
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the text does not have the same length as the suffix array.
+    pub fn distinct_substring_profile<I: InputElement>(
+        &self,
+        text: &[I],
+        max_len: usize,
+    ) -> Vec<usize> {
+        // every suffix adds one substring for each length in (lcp, suffix_len]
+        let mut differences = vec![0isize; max_len + 1];
+
+        self.for_each_new_prefix_range(text, |lcp, suffix_len| {
+            if lcp < max_len {
+                differences[lcp] += 1;
+                differences[suffix_len.min(max_len)] -= 1;
+            }
+        });
+
+        differences[..max_len]
+            .iter()
+            .scan(0, |num_substrings, &difference| {
+                *num_substrings += difference;
+                Some(*num_substrings as usize)
+            })
+            .collect()
+    }
+
+    // calls f with the LCP value and the length of every suffix in suffix array order, where for generalized
+    // suffix arrays, the suffixes end before the next separator
+    fn for_each_new_prefix_range<I: InputElement>(
+        &self,
+        text: &[I],
+        mut f: impl FnMut(usize, usize),
+    ) {
+        assert_eq!(
+            text.len(),
+            self.suffix_array().len(),
+            "The text must have the same length as the suffix array"
+        );
+
+        let separator_distances = self.is_generalized_suffix_array().then(|| {
+            let mut next_separator = text.len();
+            let mut separator_distances = vec![0; text.len()];
+
+            for position in (0..text.len()).rev() {
+                if text[position] == I::zero() {
+                    next_separator = position;
+                }
+
+                separator_distances[position] = next_separator - position;
+            }
+
+            separator_distances
+        });
+
+        for (suffix_array_index, &suffix) in self.suffix_array().iter().enumerate() {
+            let suffix = <usize as NumCast>::from(suffix).unwrap();
+            let suffix_len = separator_distances
+                .as_ref()
+                .map_or(text.len() - suffix, |distances| distances[suffix]);
+            let lcp = if suffix_array_index == 0 {
+                0
+            } else {
+                <usize as NumCast>::from(self.lcp()[suffix_array_index]).unwrap()
+            };
+
+            f(lcp, suffix_len);
+        }
+    }
+}
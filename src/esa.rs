@@ -0,0 +1,349 @@
+/*!
+ * Navigate the virtual suffix tree of a text using an [enhanced suffix array] (ESA).
+ *
+ * The internal nodes of the suffix tree correspond to the LCP intervals of the suffix array and the leaves correspond
+ * to single suffixes. The [`EnhancedSuffixArray`] combines the suffix array and the LCP array of a
+ * [`SuffixArrayWithLcpAndPlcp`] with the child table of Abouelhoda, Kurtz and Ohlebusch. The child table stores the
+ * `up`, `down` and `next-l-index` values in a single array of the output element type and allows enumerating the
+ * children of a node in constant time per child. Suffix tree algorithms can therefore run top-down, for example
+ * to find a pattern in `O(m * σ)` time, where `σ` is the size of the alphabet.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, esa::EnhancedSuffixArray};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let esa = EnhancedSuffixArray::new(&res, text);
+ *
+ * let root = esa.root();
+ * assert_eq!(esa.children(root).count(), 5);
+ *
+ * let node = esa.child(root, b'a').unwrap();
+ * assert_eq!(node.depth(), 1);
+ * assert_eq!(node.interval().len(), 5);
+ *
+ * let node = esa.child(node, b'b').unwrap();
+ * assert_eq!(node.depth(), 4);
+ * assert_eq!(esa.locate_sorted(b"abr"), vec![0, 7]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The suffix array of `libsais` does not contain the suffix that consists only of the sentinel. As a consequence,
+ * the root of the virtual suffix tree has a depth greater than 0 if all suffixes start with the same symbol, which
+ * only happens for texts that consist of a single repeated symbol. A leaf for a suffix that is a prefix of another
+ * suffix has no outgoing symbol and is never returned by [`EnhancedSuffixArray::child`].
+ *
+ * For generalized suffix arrays, the zero separators are treated as unique symbols by the LCP array. Therefore,
+ * every suffix that starts with a separator is a leaf child of the root.
+ *
+ * [enhanced suffix array]: https://doi.org/10.1016/S1570-8667(03)00065-0
+ */
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement,
+    lcp::SuffixArrayWithLcpAndPlcp,
+    search::{Occurrences, SuffixArrayInterval, symbol_at},
+    typestate::BufferMode,
+};
+
+/// A node of the virtual suffix tree. It is either an LCP interval or a leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EsaNode {
+    interval: SuffixArrayInterval,
+    depth: usize,
+}
+
+impl EsaNode {
+    /// The suffix array interval of the suffixes in the subtree of this node.
+    pub fn interval(&self) -> SuffixArrayInterval {
+        self.interval
+    }
+
+    /// The length of the string that is spelled by the path from the root to this node.
+    ///
+    /// For internal nodes, this is the LCP value of the interval. For leaves, it is the length of the suffix.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.interval.len() == 1
+    }
+}
+
+/// A suffix array with LCP array and child table.
+///
+/// See [`esa`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnhancedSuffixArray<'a, I: InputElement, O: OutputElement> {
+    text: &'a [I],
+    suffix_array: &'a [O],
+    lcp: &'a [O],
+    // child[i] stores up[i + 1] if lcp[i] > lcp[i + 1], otherwise next_l[i] if it is defined and
+    // otherwise down[i]. 0 is used for undefined values, because it can never be a valid value.
+    child: Vec<O>,
+}
+
+impl<'a, I: InputElement, O: OutputElement> EnhancedSuffixArray<'a, I, O> {
+    /// Compute the child table from the LCP array of `index`.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the text does not have the same length as the suffix array.
+    pub fn new<SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>(
+        index: &'a SuffixArrayWithLcpAndPlcp<'_, '_, '_, O, SaB, LcpB, PlcpB>,
+        text: &'a [I],
+    ) -> Self {
+        assert_eq!(
+            text.len(),
+            index.suffix_array().len(),
+            "The text must have the same length as the suffix array"
+        );
+
+        let mut esa = Self {
+            text,
+            suffix_array: index.suffix_array(),
+            lcp: index.lcp(),
+            child: vec![O::zero(); text.len()],
+        };
+
+        esa.fill_up_and_down();
+        esa.fill_next_l();
+
+        esa
+    }
+
+    fn fill_up_and_down(&mut self) {
+        let mut stack = vec![0];
+        let mut last_index = None;
+
+        for i in 1..=self.len() {
+            while self.lcp_value(i) < self.lcp_value(*stack.last().unwrap()) {
+                let popped = stack.pop().unwrap();
+                last_index = Some(popped);
+                let top = *stack.last().unwrap();
+
+                if self.lcp_value(i) <= self.lcp_value(top)
+                    && self.lcp_value(top) != self.lcp_value(popped)
+                {
+                    // down[top] = popped
+                    self.child[top] = <O as NumCast>::from(popped).unwrap();
+                }
+            }
+
+            if let Some(last_index) = last_index.take() {
+                // up[i] = last_index
+                self.child[i - 1] = <O as NumCast>::from(last_index).unwrap();
+            }
+
+            stack.push(i);
+        }
+    }
+
+    fn fill_next_l(&mut self) {
+        let mut stack = vec![0];
+
+        for i in 1..=self.len() {
+            while self.lcp_value(i) < self.lcp_value(*stack.last().unwrap()) {
+                stack.pop();
+            }
+
+            let top = *stack.last().unwrap();
+
+            if self.lcp_value(i) == self.lcp_value(top) {
+                stack.pop();
+                // next_l[top] = i
+                self.child[top] = <O as NumCast>::from(i).unwrap();
+            }
+
+            stack.push(i);
+        }
+    }
+
+    pub fn text(&self) -> &'a [I] {
+        self.text
+    }
+
+    pub fn suffix_array(&self) -> &'a [O] {
+        self.suffix_array
+    }
+
+    pub fn lcp(&self) -> &'a [O] {
+        self.lcp
+    }
+
+    /// The child table in the compact representation of Abouelhoda, Kurtz and Ohlebusch.
+    pub fn child_table(&self) -> &[O] {
+        &self.child
+    }
+
+    /// The length of the text.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The root of the virtual suffix tree, whose interval contains all suffixes.
+    pub fn root(&self) -> EsaNode {
+        self.node(0, self.len())
+    }
+
+    /// The children of `node`, ordered by their first symbol. Leaves have no children.
+    pub fn children(&self, node: EsaNode) -> impl Iterator<Item = EsaNode> + '_ {
+        let (start, end) = (node.interval.start(), node.interval.end());
+
+        let first_l_index = if node.is_leaf() || node.interval.is_empty() {
+            None
+        } else {
+            Some(self.first_l_index(start, end))
+        };
+
+        let mut l_indices = std::iter::successors(first_l_index, move |&l_index| {
+            self.next_l(l_index)
+                .filter(|&next_l_index| next_l_index < end)
+        });
+
+        let mut child_start = start;
+
+        std::iter::from_fn(move || {
+            if first_l_index.is_none() || child_start == end {
+                return None;
+            }
+
+            let child_end = l_indices.next().unwrap_or(end);
+            let child = self.node(child_start, child_end);
+            child_start = child_end;
+
+            Some(child)
+        })
+    }
+
+    /// The child of `node` whose edge label starts with `symbol`, if it exists.
+    pub fn child(&self, node: EsaNode, symbol: I) -> Option<EsaNode> {
+        self.children(node).find(|child| {
+            let suffix = self.suffix_array[child.interval.start()];
+            symbol_at(self.text, suffix, node.depth) == Some(symbol)
+        })
+    }
+
+    /// The suffix array interval of all suffixes that start with `pattern`, found via top-down traversal.
+    pub fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        let mut node = self.root();
+        let mut match_len = 0;
+
+        loop {
+            let suffix = self.suffix_array.get(node.interval.start());
+            let compare_until = node.depth.min(pattern.len());
+
+            let edge_matches = suffix.is_some_and(|&suffix| {
+                (match_len..compare_until)
+                    .all(|offset| symbol_at(self.text, suffix, offset) == Some(pattern[offset]))
+            });
+
+            if !edge_matches {
+                return SuffixArrayInterval::new(0, 0);
+            }
+
+            if compare_until == pattern.len() {
+                return node.interval;
+            }
+
+            match_len = compare_until;
+
+            match self.child(node, pattern[match_len]) {
+                Some(child) => node = child,
+                None => return SuffixArrayInterval::new(0, 0),
+            }
+        }
+    }
+
+    /// The number of occurrences of `pattern` in the text.
+    pub fn count(&self, pattern: &[I]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    pub fn locate(&self, pattern: &[I]) -> Occurrences<'a, O> {
+        Occurrences::new(&self.suffix_array[self.interval(pattern).range()])
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    pub fn locate_sorted(&self, pattern: &[I]) -> Vec<usize> {
+        self.locate(pattern).into_sorted_vec()
+    }
+
+    // node for the half-open interval [start, end)
+    fn node(&self, start: usize, end: usize) -> EsaNode {
+        let depth = if end - start == 1 {
+            self.len() - <usize as NumCast>::from(self.suffix_array[start]).unwrap()
+        } else if end - start > 1 {
+            self.lcp_value(self.first_l_index(start, end)) as usize
+        } else {
+            0
+        };
+
+        EsaNode {
+            interval: SuffixArrayInterval::new(start, end),
+            depth,
+        }
+    }
+
+    // first l-index of the LCP interval [start, end) with at least two elements
+    fn first_l_index(&self, start: usize, end: usize) -> usize {
+        match self.up(end) {
+            Some(up) if start < up && up < end => up,
+            _ => self.down(start).unwrap(),
+        }
+    }
+
+    fn up(&self, i: usize) -> Option<usize> {
+        (i > 0 && self.lcp_value(i - 1) > self.lcp_value(i)).then(|| self.child_value(i - 1))
+    }
+
+    fn down(&self, i: usize) -> Option<usize> {
+        let value = self.child_value(i);
+        (value > i && self.lcp_value(value) > self.lcp_value(i)).then_some(value)
+    }
+
+    fn next_l(&self, i: usize) -> Option<usize> {
+        let value = self.child_value(i);
+        (value > i && self.lcp_value(value) == self.lcp_value(i)).then_some(value)
+    }
+
+    fn child_value(&self, i: usize) -> usize {
+        self.child
+            .get(i)
+            .map_or(0, |&value| <usize as NumCast>::from(value).unwrap())
+    }
+
+    // the LCP array with virtual values of -1 at the first and past the last position
+    fn lcp_value(&self, i: usize) -> isize {
+        if i == 0 || i == self.len() {
+            -1
+        } else {
+            <isize as NumCast>::from(self.lcp[i]).unwrap()
+        }
+    }
+}
@@ -0,0 +1,590 @@
+/*!
+ * Count and locate patterns in a text using an [FM-index] built from its Burrows-Wheeler-Transform.
+ *
+ * The [`FmIndex`] is built from the return types of a [`BwtConstruction`](super::BwtConstruction), which are
+ * [`Bwt`] and [`BwtWithAuxIndices`]. It consists of the BWT, the C array (the number of symbols in the text that
+ * are smaller than a given symbol), an occurrence table for rank queries on the BWT and a sample of the
+ * suffix array. The text itself is not needed after the construction of the BWT.
+ *
+ * The occurrence table stores the number of occurrences of every symbol at regular intervals of the BWT. For texts
+ * with more than 256 distinct symbols, which is only possible for `u16`-based texts, this would need too much
+ * memory. In that case, a [`WaveletMatrix`] is used for the rank queries instead.
+ *
+ * Patterns are found via backward search in `O(m)` rank queries, where `m` is the length of the pattern.
+ * To locate an occurrence, the LF mapping is applied until a sampled suffix array entry is found. With a
+ * sampling rate of `s`, every `s`-th text position is sampled and locating an occurrence needs at most `s - 1`
+ * LF steps. A larger sampling rate leads to a smaller index and slower locate queries.
+ *
+ * ```
+ * use libsais::{BwtConstruction, fm_index::FmIndex};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let bwt = BwtConstruction::for_text(text)
+ *     .with_owned_temporary_array_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let index = FmIndex::new(&bwt, 4);
+ *
+ * assert_eq!(index.count(b"abra"), 2);
+ * assert_eq!(index.locate_sorted(b"abra"), vec![0, 7]);
+ * ```
+ *
+ * The rows of the sampled text positions are stored as well, such that [`FmIndex::extract`] can recover a substring
+ * of length `l` of the text with at most `s + l` LF steps.
+ *
+ * When the BWT was constructed with auxiliary indices, [`FmIndex::from_aux_indices`] uses them as the suffix array
+ * sample. In that case, the sampling rate of the auxiliary indices is also the sampling rate of the index.
+ *
+ * # Sentinel Convention
+ *
+ * The BWT of `libsais` does not contain the sentinel (see [`bwt`](super::bwt#output-conventions)). The FM-index
+ * internally reinserts the sentinel at the primary index. All intervals returned by the index are intervals of the
+ * suffix array in the convention of `libsais`, i.e. the suffix array without the entry for the sentinel. They can be
+ * used interchangeably with the intervals returned by the [`search`](super::search) functions.
+ *
+ * [FM-index]: https://doi.org/10.1109/SFCS.2000.892127
+ */
+
+use std::{borrow::Cow, ops::Range};
+
+use num_traits::NumCast;
+
+use crate::{
+    OutputElement, SmallAlphabet,
+    bitvec::BitVector,
+    bwt::{Bwt, BwtWithAuxIndices},
+    search::SuffixArrayInterval,
+    typestate::BufferMode,
+    wavelet_matrix::WaveletMatrix,
+};
+
+// number of BWT positions between two checkpoints of the occurrence table
+const OCCURRENCE_TABLE_BLOCK_SIZE: usize = 64;
+
+// for texts with more distinct symbols, a wavelet matrix is used instead of the occurrence table
+const MAX_OCCURRENCE_TABLE_SYMBOLS: usize = 256;
+
+/// An FM-index for `u8`/`u16`-based texts.
+///
+/// See [`fm_index`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FmIndex<I: SmallAlphabet> {
+    lf_mapping: LfMapping<'static, I>,
+    sampled_rows: BitVector,
+    samples: Vec<usize>,
+    // rows of every sampling_rate-th text position
+    inverse_samples: Vec<usize>,
+    sampling_rate: usize,
+}
+
+impl<I: SmallAlphabet> FmIndex<I> {
+    /// Build the index and sample every `sampling_rate`-th text position of the suffix array.
+    ///
+    /// The suffix array sample is computed by applying the LF mapping for every position of the text.
+    ///
+    /// # Panics
+    ///
+    /// If the sampling rate is 0.
+    pub fn new<B: BufferMode>(bwt: &Bwt<'_, I, B>, sampling_rate: usize) -> Self {
+        assert!(sampling_rate > 0, "Sampling rate cannot be 0");
+
+        let mut index = Self::without_samples(bwt.bwt(), bwt.primary_index(), sampling_rate);
+
+        let text_len = index.len();
+        let mut sampled_rows_and_positions = vec![(0, text_len)];
+        let mut row = 0;
+
+        for text_position in (0..text_len).rev() {
+            row = index.lf(row);
+
+            if text_position % sampling_rate == 0 {
+                sampled_rows_and_positions.push((row, text_position));
+            }
+        }
+
+        index.set_samples(sampled_rows_and_positions);
+
+        index
+    }
+
+    /// Build the index and use the auxiliary indices of the BWT as the suffix array sample.
+    ///
+    /// See [`bwt`](super::bwt#primary-index-and-auxiliary-indices) for details on auxiliary indices.
+    pub fn from_aux_indices<O: OutputElement, BwtB: BufferMode, AuxB: BufferMode>(
+        bwt: &BwtWithAuxIndices<'_, '_, I, O, BwtB, AuxB>,
+    ) -> Self {
+        let sampling_rate = bwt.aux_indices_sampling_rate().value();
+        let mut index = Self::without_samples(bwt.bwt(), bwt.primary_index(), sampling_rate);
+
+        // AUX[i] == k => SUF[k - 1] = i * r, and row k of the BWT matrix corresponds to SUF[k - 1]
+        let sampled_rows_and_positions = std::iter::once((0, index.len()))
+            .chain(
+                bwt.aux_indices()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &row)| (<usize as NumCast>::from(row).unwrap(), i * sampling_rate)),
+            )
+            .collect();
+
+        index.set_samples(sampled_rows_and_positions);
+
+        index
+    }
+
+    fn without_samples(bwt: &[I], primary_index: usize, sampling_rate: usize) -> Self {
+        Self {
+            lf_mapping: LfMapping::new(Cow::Owned(bwt.to_vec()), primary_index),
+            sampled_rows: BitVector::from_ones(0, []),
+            samples: Vec::new(),
+            inverse_samples: Vec::new(),
+            sampling_rate,
+        }
+    }
+
+    fn set_samples(&mut self, mut sampled_rows_and_positions: Vec<(usize, usize)>) {
+        sampled_rows_and_positions.sort_unstable();
+        sampled_rows_and_positions.dedup();
+
+        self.inverse_samples = vec![0; self.len().div_ceil(self.sampling_rate)];
+        for &(row, text_position) in &sampled_rows_and_positions {
+            if text_position < self.len() {
+                self.inverse_samples[text_position / self.sampling_rate] = row;
+            }
+        }
+
+        self.sampled_rows = BitVector::from_ones(
+            self.len() + 1,
+            sampled_rows_and_positions.iter().map(|&(row, _)| row),
+        );
+        self.samples = sampled_rows_and_positions
+            .into_iter()
+            .map(|(_, text_position)| text_position)
+            .collect();
+    }
+
+    /// The length of the indexed text.
+    pub fn len(&self) -> usize {
+        self.lf_mapping.bwt().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lf_mapping.bwt().is_empty()
+    }
+
+    /// The BWT in the convention of `libsais`, without the sentinel.
+    pub fn bwt(&self) -> &[I] {
+        self.lf_mapping.bwt()
+    }
+
+    pub fn primary_index(&self) -> usize {
+        self.lf_mapping.primary_index()
+    }
+
+    pub fn sampling_rate(&self) -> usize {
+        self.sampling_rate
+    }
+
+    /// The suffix array interval of all suffixes that start with `pattern`, found via backward search.
+    pub fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        let (mut start_row, mut end_row) = (0, self.len() + 1);
+
+        for &symbol in pattern.iter().rev() {
+            if start_row == end_row {
+                break;
+            }
+
+            (start_row, end_row) = self.lf_interval(start_row, end_row, symbol);
+        }
+
+        if pattern.is_empty() {
+            SuffixArrayInterval::new(0, self.len())
+        } else {
+            // the rows are shifted by one compared to the suffix array because of the sentinel row
+            SuffixArrayInterval::new(start_row.max(1) - 1, end_row.max(1) - 1)
+        }
+    }
+
+    /// The number of occurrences of `pattern` in the text.
+    pub fn count(&self, pattern: &[I]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    pub fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        self.interval(pattern)
+            .range()
+            .map(|suffix_array_index| self.suffix_array_entry(suffix_array_index))
+            .collect()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    pub fn locate_sorted(&self, pattern: &[I]) -> Vec<usize> {
+        let mut positions = self.locate(pattern);
+        positions.sort_unstable();
+        positions
+    }
+
+    /// The entry of the suffix array at `suffix_array_index`, computed using the suffix array sample.
+    ///
+    /// # Panics
+    ///
+    /// If the index is out of bounds.
+    pub fn suffix_array_entry(&self, suffix_array_index: usize) -> usize {
+        assert!(
+            suffix_array_index < self.len(),
+            "Suffix array index out of bounds"
+        );
+
+        let mut row = suffix_array_index + 1;
+        let mut num_steps = 0;
+
+        while !self.sampled_rows.get(row) {
+            row = self.lf(row);
+            num_steps += 1;
+        }
+
+        self.samples[self.sampled_rows.rank1(row)] + num_steps
+    }
+
+    /// The substring of the text in the given range, computed using the LF mapping from the next sampled
+    /// text position after the range.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    pub fn extract(&self, range: Range<usize>) -> Vec<I> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Range out of bounds"
+        );
+
+        let sample_index = range.end.div_ceil(self.sampling_rate);
+
+        // the sentinel row 0 belongs to the end of the text
+        let (text_position, row) = match self.inverse_samples.get(sample_index) {
+            Some(&row) => (sample_index * self.sampling_rate, row),
+            None => (self.len(), 0),
+        };
+
+        extract_with_lf(
+            range,
+            text_position,
+            row,
+            |row| self.last_column_symbol(row),
+            |row| self.lf(row),
+        )
+    }
+
+    // the symbol in the last column of the BWT matrix, None for the sentinel
+    pub(crate) fn last_column_symbol(&self, row: usize) -> Option<I> {
+        self.lf_mapping.last_column_symbol(row)
+    }
+
+    // number of rows in [start_row, end_row) whose symbol in the last column of the BWT matrix is
+    // the sentinel or smaller than symbol
+    pub(crate) fn count_smaller(&self, symbol: I, start_row: usize, end_row: usize) -> usize {
+        self.lf_mapping.count_smaller(symbol, start_row, end_row)
+    }
+
+    // the distinct symbols in the last column of the BWT matrix in [start_row, end_row), excluding the
+    // sentinel, in ascending order
+    pub(crate) fn symbols_in_rows(&self, start_row: usize, end_row: usize) -> Vec<I> {
+        self.lf_mapping.symbols_in_rows(start_row, end_row)
+    }
+
+    fn lf(&self, row: usize) -> usize {
+        self.lf_mapping.lf(row)
+    }
+
+    pub(crate) fn lf_interval(
+        &self,
+        start_row: usize,
+        end_row: usize,
+        symbol: I,
+    ) -> (usize, usize) {
+        self.lf_mapping.lf_interval(start_row, end_row, symbol)
+    }
+}
+
+// The BWT with the C array and the rank support for the LF mapping. The BWT is borrowed when
+// substrings are extracted from a BWT with auxiliary indices and owned by the FM-index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct LfMapping<'b, I: SmallAlphabet> {
+    bwt: Cow<'b, [I]>,
+    primary_index: usize,
+    // counts[c] is the first row of the BWT matrix that starts with c, including the sentinel row
+    counts: Vec<usize>,
+    occurrences: RankSupport<I>,
+}
+
+impl<'b, I: SmallAlphabet> LfMapping<'b, I> {
+    pub(crate) fn new(bwt: Cow<'b, [I]>, primary_index: usize) -> Self {
+        let mut frequencies = vec![0; I::FREQUENCY_TABLE_SIZE];
+        for &symbol in bwt.iter() {
+            frequencies[symbol_index(symbol)] += 1;
+        }
+
+        let mut counts = Vec::with_capacity(I::FREQUENCY_TABLE_SIZE + 1);
+        // the sentinel is the smallest symbol
+        let mut count = 1;
+
+        for frequency in &frequencies {
+            counts.push(count);
+            count += frequency;
+        }
+        counts.push(count);
+
+        let num_distinct_symbols = frequencies
+            .iter()
+            .filter(|&&frequency| frequency > 0)
+            .count();
+
+        let occurrences = if num_distinct_symbols > MAX_OCCURRENCE_TABLE_SYMBOLS {
+            RankSupport::WaveletMatrix(WaveletMatrix::new(&bwt))
+        } else {
+            RankSupport::OccurrenceTable(OccurrenceTable::new(&bwt))
+        };
+
+        Self {
+            bwt,
+            primary_index,
+            counts,
+            occurrences,
+        }
+    }
+
+    pub(crate) fn bwt(&self) -> &[I] {
+        &self.bwt
+    }
+
+    pub(crate) fn primary_index(&self) -> usize {
+        self.primary_index
+    }
+
+    // the symbol in the last column of the BWT matrix, None for the sentinel
+    pub(crate) fn last_column_symbol(&self, row: usize) -> Option<I> {
+        if row < self.primary_index {
+            Some(self.bwt[row])
+        } else if row == self.primary_index {
+            None
+        } else {
+            Some(self.bwt[row - 1])
+        }
+    }
+
+    // number of occurrences of symbol in the last column of the BWT matrix, before row
+    fn rank(&self, symbol: I, row: usize) -> usize {
+        self.occurrences
+            .rank(&self.bwt, symbol, self.bwt_index(row))
+    }
+
+    // number of rows in [start_row, end_row) whose symbol in the last column of the BWT matrix is
+    // the sentinel or smaller than symbol
+    pub(crate) fn count_smaller(&self, symbol: I, start_row: usize, end_row: usize) -> usize {
+        let contains_sentinel = (start_row..end_row).contains(&self.primary_index);
+        let (start, end) = (self.bwt_index(start_row), self.bwt_index(end_row));
+
+        let count_smaller_symbols = match &self.occurrences {
+            RankSupport::OccurrenceTable(occurrence_table) => {
+                occurrence_table.rank_smaller(&self.bwt, symbol, end)
+                    - occurrence_table.rank_smaller(&self.bwt, symbol, start)
+            }
+            RankSupport::WaveletMatrix(wavelet_matrix) => {
+                wavelet_matrix.count_less_than(symbol, start, end)
+            }
+        };
+
+        contains_sentinel as usize + count_smaller_symbols
+    }
+
+    // the distinct symbols in the last column of the BWT matrix in [start_row, end_row), excluding the
+    // sentinel, in ascending order
+    pub(crate) fn symbols_in_rows(&self, start_row: usize, end_row: usize) -> Vec<I> {
+        let (start, end) = (self.bwt_index(start_row), self.bwt_index(end_row));
+
+        match &self.occurrences {
+            RankSupport::OccurrenceTable(occurrence_table) => occurrence_table
+                .symbols
+                .iter()
+                .map(|&symbol| <I as NumCast>::from(symbol).unwrap())
+                .filter(|&symbol| {
+                    occurrence_table.rank(&self.bwt, symbol, end)
+                        > occurrence_table.rank(&self.bwt, symbol, start)
+                })
+                .collect(),
+            RankSupport::WaveletMatrix(wavelet_matrix) => wavelet_matrix
+                .distinct_in_range(start, end)
+                .into_iter()
+                .map(|(symbol, _)| symbol)
+                .collect(),
+        }
+    }
+
+    // maps a row of the BWT matrix to the index of the BWT without sentinel
+    fn bwt_index(&self, row: usize) -> usize {
+        if row <= self.primary_index {
+            row
+        } else {
+            row - 1
+        }
+    }
+
+    pub(crate) fn lf(&self, row: usize) -> usize {
+        match self.last_column_symbol(row) {
+            Some(symbol) => self.counts[symbol_index(symbol)] + self.rank(symbol, row),
+            None => 0,
+        }
+    }
+
+    pub(crate) fn lf_interval(
+        &self,
+        start_row: usize,
+        end_row: usize,
+        symbol: I,
+    ) -> (usize, usize) {
+        let count = self.counts[symbol_index(symbol)];
+
+        (
+            count + self.rank(symbol, start_row),
+            count + self.rank(symbol, end_row),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RankSupport<I: SmallAlphabet> {
+    OccurrenceTable(OccurrenceTable),
+    WaveletMatrix(WaveletMatrix<I>),
+}
+
+impl<I: SmallAlphabet> RankSupport<I> {
+    // number of occurrences of symbol in the BWT before bwt_index
+    fn rank(&self, bwt: &[I], symbol: I, bwt_index: usize) -> usize {
+        match self {
+            Self::OccurrenceTable(occurrence_table) => {
+                occurrence_table.rank(bwt, symbol, bwt_index)
+            }
+            Self::WaveletMatrix(wavelet_matrix) => wavelet_matrix.rank(symbol, bwt_index),
+        }
+    }
+}
+
+// Stores the number of occurrences of every symbol that occurs in the BWT before every block of
+// the BWT. Rank queries scan at most one block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct OccurrenceTable {
+    // the symbols that occur in the BWT, in ascending order
+    symbols: Vec<usize>,
+    // maps symbols to their column in the checkpoints, usize::MAX for symbols that don't occur
+    symbol_columns: Vec<usize>,
+    num_columns: usize,
+    checkpoints: Vec<usize>,
+}
+
+impl OccurrenceTable {
+    fn new<I: SmallAlphabet>(bwt: &[I]) -> Self {
+        let mut symbol_columns = vec![usize::MAX; I::FREQUENCY_TABLE_SIZE];
+        let mut num_columns = 0;
+
+        for &symbol in bwt {
+            let column = &mut symbol_columns[symbol_index(symbol)];
+
+            if *column == usize::MAX {
+                *column = num_columns;
+                num_columns += 1;
+            }
+        }
+
+        let num_checkpoints = bwt.len().div_ceil(OCCURRENCE_TABLE_BLOCK_SIZE) + 1;
+        let mut checkpoints = Vec::with_capacity(num_checkpoints * num_columns);
+        let mut current_counts = vec![0; num_columns];
+
+        for block in bwt.chunks(OCCURRENCE_TABLE_BLOCK_SIZE) {
+            checkpoints.extend_from_slice(&current_counts);
+
+            for &symbol in block {
+                current_counts[symbol_columns[symbol_index(symbol)]] += 1;
+            }
+        }
+
+        checkpoints.extend_from_slice(&current_counts);
+
+        let symbols = (0..I::FREQUENCY_TABLE_SIZE)
+            .filter(|&symbol| symbol_columns[symbol] != usize::MAX)
+            .collect();
+
+        Self {
+            symbols,
+            symbol_columns,
+            num_columns,
+            checkpoints,
+        }
+    }
+
+    fn rank<I: SmallAlphabet>(&self, bwt: &[I], symbol: I, bwt_index: usize) -> usize {
+        let column = self.symbol_columns[symbol_index(symbol)];
+
+        if column == usize::MAX {
+            return 0;
+        }
+
+        let block = bwt_index / OCCURRENCE_TABLE_BLOCK_SIZE;
+        let block_start = block * OCCURRENCE_TABLE_BLOCK_SIZE;
+
+        self.checkpoints[block * self.num_columns + column]
+            + bwt[block_start..bwt_index]
+                .iter()
+                .filter(|&&c| c == symbol)
+                .count()
+    }
+
+    // number of occurrences of symbols smaller than symbol in the BWT before bwt_index
+    fn rank_smaller<I: SmallAlphabet>(&self, bwt: &[I], symbol: I, bwt_index: usize) -> usize {
+        let block = bwt_index / OCCURRENCE_TABLE_BLOCK_SIZE;
+        let block_start = block * OCCURRENCE_TABLE_BLOCK_SIZE;
+        let checkpoint = &self.checkpoints[block * self.num_columns..];
+
+        self.symbol_columns[..symbol_index(symbol)]
+            .iter()
+            .filter(|&&column| column != usize::MAX)
+            .map(|&column| checkpoint[column])
+            .sum::<usize>()
+            + bwt[block_start..bwt_index]
+                .iter()
+                .filter(|&&c| c < symbol)
+                .count()
+    }
+}
+
+// Extracts the text in range from right to left, starting at the row of the BWT matrix that belongs
+// to text_position, which must not be smaller than the end of the range.
+pub(crate) fn extract_with_lf<I: SmallAlphabet>(
+    range: Range<usize>,
+    mut text_position: usize,
+    mut row: usize,
+    last_column_symbol: impl Fn(usize) -> Option<I>,
+    lf: impl Fn(usize) -> usize,
+) -> Vec<I> {
+    let mut extracted = Vec::with_capacity(range.len());
+
+    while text_position > range.start {
+        // the last column of a row contains the symbol before the suffix of the row
+        text_position -= 1;
+
+        if text_position < range.end {
+            extracted.push(last_column_symbol(row).unwrap());
+        }
+
+        row = lf(row);
+    }
+
+    extracted.reverse();
+    extracted
+}
+
+pub(crate) fn symbol_index<I: SmallAlphabet>(symbol: I) -> usize {
+    <usize as NumCast>::from(symbol).unwrap()
+}
@@ -0,0 +1,309 @@
+/*!
+ * Answer range minimum queries (RMQ) and longest common extension (LCE) queries in constant time.
+ *
+ * The [`RangeMinimumQuery`] structure can be built for any array of output elements, but it is mainly intended for the
+ * LCP array. It divides the array into blocks of 64 elements and uses a sparse table over the block minima. Queries
+ * inside of a block are answered using bit masks that represent the stack of previous smaller values, such that every
+ * query needs a constant number of operations. The structure needs `n + O(n log n / 64)` words of space in addition
+ * to the array.
+ *
+ * The [`LceIndex`] combines an RMQ structure over the LCP array with the inverse suffix array. The longest common
+ * prefix of two arbitrary suffixes `i` and `j` is the minimum of the LCP array between the positions of the suffixes
+ * in the suffix array, so [`LceIndex::lce`] runs in constant time.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, lce::LceIndex};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let index = LceIndex::new(&res);
+ *
+ * assert_eq!(index.lce(0, 7), 4);
+ * assert_eq!(index.lce(1, 8), 3);
+ * assert_eq!(index.lce(2, 4), 0);
+ * ```
+ *
+ * Additionally, this module provides the [`previous_smaller_values`] and [`next_smaller_values`] arrays, which can be
+ * computed for any array of output elements in linear time. They are a building block of many algorithms on suffix
+ * arrays and LCP arrays.
+ *
+ * # Generalized Suffix Array Support
+ *
+ * For generalized suffix arrays, the LCP values stop at the zero separators (see
+ * [`lcp`](super::lcp#generalized-suffix-array-support)). Therefore, the LCE of two different suffixes also stops at
+ * the end of the texts in the set.
+ */
+
+use num_traits::NumCast;
+
+use crate::{OutputElement, lcp::SuffixArrayWithLcpAndPlcp, typestate::BufferMode};
+
+const BLOCK_SIZE: usize = 64;
+
+/// A structure for constant time range minimum queries on an array of output elements.
+///
+/// See [`lce`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeMinimumQuery<'a, O: OutputElement> {
+    values: &'a [O],
+    // bit j of in_block_masks[i] is set if position j of the block of i is a previous smaller
+    // value of i (or i itself), i.e. on the stack of the left-to-right minima ending at i
+    in_block_masks: Vec<u64>,
+    // block_minima[k][b] is the position of the minimum of the blocks [b, b + 2^k)
+    block_minima: Vec<Vec<usize>>,
+}
+
+impl<'a, O: OutputElement> RangeMinimumQuery<'a, O> {
+    pub fn new(values: &'a [O]) -> Self {
+        let mut in_block_masks = vec![0; values.len()];
+        let mut stack: Vec<usize> = Vec::with_capacity(BLOCK_SIZE);
+        let mut first_level = Vec::with_capacity(values.len().div_ceil(BLOCK_SIZE));
+
+        for (block_index, block) in values.chunks(BLOCK_SIZE).enumerate() {
+            let block_start = block_index * BLOCK_SIZE;
+            let mut mask = 0u64;
+            stack.clear();
+
+            for (offset, value) in block.iter().enumerate() {
+                while let Some(&top) = stack.last() {
+                    if block[top] <= *value {
+                        break;
+                    }
+
+                    stack.pop();
+                    mask &= !(1u64 << top);
+                }
+
+                stack.push(offset);
+                mask |= 1u64 << offset;
+                in_block_masks[block_start + offset] = mask;
+            }
+
+            // the bottom of the stack is the leftmost minimum of the block
+            first_level.push(block_start + stack[0]);
+        }
+
+        let mut block_minima = vec![first_level];
+        let num_blocks = block_minima[0].len();
+
+        while 1 << block_minima.len() <= num_blocks {
+            let previous_level = block_minima.last().unwrap();
+            let half_width = 1 << (block_minima.len() - 1);
+
+            let level = (0..=num_blocks - 2 * half_width)
+                .map(|block| {
+                    Self::leftmost_min_position(
+                        values,
+                        previous_level[block],
+                        previous_level[block + half_width],
+                    )
+                })
+                .collect();
+
+            block_minima.push(level);
+        }
+
+        Self {
+            values,
+            in_block_masks,
+            block_minima,
+        }
+    }
+
+    pub fn values(&self) -> &'a [O] {
+        self.values
+    }
+
+    /// The position of the leftmost minimum in the half-open range `[start, end)`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is empty or out of bounds.
+    pub fn query(&self, start: usize, end: usize) -> usize {
+        assert!(
+            start < end && end <= self.values.len(),
+            "Range must be non-empty and inside of the array"
+        );
+
+        let last = end - 1;
+        let (start_block, last_block) = (start / BLOCK_SIZE, last / BLOCK_SIZE);
+
+        if start_block == last_block {
+            return self.in_block_query(start, last);
+        }
+
+        let mut min_position =
+            self.in_block_query(start, start_block * BLOCK_SIZE + BLOCK_SIZE - 1);
+
+        if start_block + 1 < last_block {
+            let num_blocks = last_block - start_block - 1;
+            let level = num_blocks.ilog2() as usize;
+
+            for block in [start_block + 1, last_block - (1 << level)] {
+                min_position = Self::leftmost_min_position(
+                    self.values,
+                    min_position,
+                    self.block_minima[level][block],
+                );
+            }
+        }
+
+        Self::leftmost_min_position(
+            self.values,
+            min_position,
+            self.in_block_query(last_block * BLOCK_SIZE, last),
+        )
+    }
+
+    /// The minimum value in the half-open range `[start, end)`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is empty or out of bounds.
+    pub fn min(&self, start: usize, end: usize) -> O {
+        self.values[self.query(start, end)]
+    }
+
+    // inclusive range inside of a single block
+    fn in_block_query(&self, start: usize, last: usize) -> usize {
+        let block_start = start - start % BLOCK_SIZE;
+        let mask = self.in_block_masks[last] & (u64::MAX << (start - block_start));
+
+        block_start + mask.trailing_zeros() as usize
+    }
+
+    // expects left_position <= right_position in case of ties
+    fn leftmost_min_position(values: &[O], left_position: usize, right_position: usize) -> usize {
+        if values[right_position] < values[left_position] {
+            right_position
+        } else {
+            left_position
+        }
+    }
+}
+
+/// An index for constant time longest common extension queries.
+///
+/// See [`lce`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LceIndex<'a, O: OutputElement> {
+    lcp_rmq: RangeMinimumQuery<'a, O>,
+    inverse_suffix_array: Vec<O>,
+}
+
+impl<'a, O: OutputElement> LceIndex<'a, O> {
+    /// Build the RMQ structure over the LCP array of `index` and compute the inverse suffix array.
+    pub fn new<SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>(
+        index: &'a SuffixArrayWithLcpAndPlcp<'_, '_, '_, O, SaB, LcpB, PlcpB>,
+    ) -> Self {
+        Self {
+            lcp_rmq: RangeMinimumQuery::new(index.lcp()),
+            inverse_suffix_array: inverse_suffix_array(index.suffix_array()),
+        }
+    }
+
+    pub fn lcp_rmq(&self) -> &RangeMinimumQuery<'a, O> {
+        &self.lcp_rmq
+    }
+
+    pub fn inverse_suffix_array(&self) -> &[O] {
+        &self.inverse_suffix_array
+    }
+
+    /// The length of the text.
+    pub fn len(&self) -> usize {
+        self.inverse_suffix_array.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inverse_suffix_array.is_empty()
+    }
+
+    /// The length of the longest common prefix of the suffixes starting at the text positions `i` and `j`.
+    ///
+    /// If `i == j`, this is the length of the suffix.
+    ///
+    /// # Panics
+    ///
+    /// If one of the positions is out of bounds.
+    pub fn lce(&self, i: usize, j: usize) -> usize {
+        if i == j {
+            assert!(i < self.len(), "Text position out of bounds");
+            return self.len() - i;
+        }
+
+        let rank_i = <usize as NumCast>::from(self.inverse_suffix_array[i]).unwrap();
+        let rank_j = <usize as NumCast>::from(self.inverse_suffix_array[j]).unwrap();
+        let (first_rank, second_rank) = (rank_i.min(rank_j), rank_i.max(rank_j));
+
+        <usize as NumCast>::from(self.lcp_rmq.min(first_rank + 1, second_rank + 1)).unwrap()
+    }
+}
+
+/// Compute the inverse suffix array, which stores for every text position the position of its suffix in the
+/// suffix array.
+pub fn inverse_suffix_array<O: OutputElement>(suffix_array: &[O]) -> Vec<O> {
+    let mut inverse_suffix_array = vec![O::zero(); suffix_array.len()];
+
+    for (rank, &suffix) in suffix_array.iter().enumerate() {
+        inverse_suffix_array[<usize as NumCast>::from(suffix).unwrap()] =
+            <O as NumCast>::from(rank).unwrap();
+    }
+
+    inverse_suffix_array
+}
+
+/// For every position `i`, the largest position `j < i` with `values[j] < values[i]`, or `-1` if there is none.
+pub fn previous_smaller_values<O: OutputElement>(values: &[O]) -> Vec<O> {
+    let mut previous_smaller_values = Vec::with_capacity(values.len());
+    let mut stack: Vec<usize> = Vec::new();
+
+    for (i, value) in values.iter().enumerate() {
+        while stack.last().is_some_and(|&top| values[top] >= *value) {
+            stack.pop();
+        }
+
+        previous_smaller_values.push(stack.last().map_or(O::zero() - O::one(), |&top| {
+            <O as NumCast>::from(top).unwrap()
+        }));
+        stack.push(i);
+    }
+
+    previous_smaller_values
+}
+
+/// For every position `i`, the smallest position `j > i` with `values[j] < values[i]`, or `n` if there is none.
+pub fn next_smaller_values<O: OutputElement>(values: &[O]) -> Vec<O> {
+    let len = <O as NumCast>::from(values.len()).unwrap();
+    let mut next_smaller_values = vec![len; values.len()];
+    let mut stack: Vec<usize> = Vec::new();
+
+    for (i, value) in values.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            if values[top] <= *value {
+                break;
+            }
+
+            next_smaller_values[top] = <O as NumCast>::from(i).unwrap();
+            stack.pop();
+        }
+
+        stack.push(i);
+    }
+
+    next_smaller_values
+}
@@ -0,0 +1,226 @@
+/*!
+ * Traverse the LCP intervals of a suffix array bottom-up, without building a suffix tree.
+ *
+ * An LCP interval `[lb, rb)` with LCP value `l` is a maximal interval of the suffix array with at least two
+ * suffixes whose longest common prefix has length `l`. The LCP intervals are exactly the internal nodes of the
+ * virtual suffix tree. Many algorithms, such as the enumeration of repeats or the computation of k-mer
+ * statistics, process these intervals bottom-up.
+ *
+ * [`SuffixArrayWithLcpAndPlcp::lcp_intervals`] returns an iterator that yields all LCP intervals in postorder,
+ * together with their child intervals. It only uses a stack, whose size is bounded by the height of the virtual
+ * suffix tree. [`SuffixArrayWithLcpAndPlcp::fold_lcp_intervals`] additionally allows computing a user-defined
+ * accumulator for every node from the accumulators of its children.
+ *
+ * ```
+ * use libsais::SuffixArrayConstruction;
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * // the lengths and numbers of occurrences of the right-maximal repeats
+ * let repeats: Vec<_> = res
+ *     .lcp_intervals()
+ *     .filter(|interval| interval.lcp() > 0)
+ *     .map(|interval| (interval.lcp(), interval.interval().len()))
+ *     .collect();
+ *
+ * assert_eq!(repeats, vec![(4, 2), (1, 5), (3, 2), (2, 2)]);
+ *
+ * // the number of leaves in the subtree of the root
+ * let num_leaves = res.fold_lcp_intervals(|_| 1, |_, children| children.into_iter().sum());
+ * assert_eq!(num_leaves, Some(11));
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The root is the interval that contains all suffixes. Since the suffix array of `libsais` does not contain the
+ * suffix that consists only of the sentinel, its LCP value is greater than 0 if the text consists of a single
+ * repeated symbol. For generalized suffix arrays, the LCP values stop at the zero separators (see
+ * [`lcp`](super::lcp#generalized-suffix-array-support)), so LCP intervals never span a separator.
+ */
+
+use num_traits::NumCast;
+
+use crate::{
+    OutputElement, lcp::SuffixArrayWithLcpAndPlcp, search::SuffixArrayInterval,
+    typestate::BufferMode,
+};
+
+/// An LCP interval, i.e. an internal node of the virtual suffix tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LcpInterval {
+    lcp: usize,
+    interval: SuffixArrayInterval,
+    child_intervals: Vec<SuffixArrayInterval>,
+}
+
+impl LcpInterval {
+    /// The length of the longest common prefix of the suffixes in the interval.
+    pub fn lcp(&self) -> usize {
+        self.lcp
+    }
+
+    pub fn interval(&self) -> SuffixArrayInterval {
+        self.interval
+    }
+
+    /// The intervals of the children in suffix array order. Leaves are intervals of length 1.
+    pub fn child_intervals(&self) -> &[SuffixArrayInterval] {
+        &self.child_intervals
+    }
+}
+
+impl<'l, 'p, 's, O: OutputElement, SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>
+    SuffixArrayWithLcpAndPlcp<'l, 'p, 's, O, SaB, LcpB, PlcpB>
+{
+    /// All LCP intervals in postorder. The root is the last interval, if the text has at least two suffixes.
+    pub fn lcp_intervals(&self) -> LcpIntervals<'_, O> {
+        LcpIntervals::new(self.lcp())
+    }
+
+    /// Compute an accumulator for every node of the virtual suffix tree bottom-up and return the one of the root.
+    ///
+    /// `leaf` is called with the suffix array index of a leaf. `node` is called for every LCP interval with the
+    /// accumulators of its children, in the order of [`LcpInterval::child_intervals`]. For a text of length 1, the
+    /// root is a leaf. For the empty text, there is no root and `None` is returned.
+    pub fn fold_lcp_intervals<A>(
+        &self,
+        mut leaf: impl FnMut(usize) -> A,
+        mut node: impl FnMut(&LcpInterval, Vec<A>) -> A,
+    ) -> Option<A> {
+        match self.lcp().len() {
+            0 => return None,
+            1 => return Some(leaf(0)),
+            _ => {}
+        }
+
+        // the accumulators of the intervals that have been visited, but not their parents
+        let mut pending = Vec::new();
+
+        for interval in self.lcp_intervals() {
+            let num_internal_children = interval
+                .child_intervals
+                .iter()
+                .filter(|child_interval| child_interval.len() > 1)
+                .count();
+
+            let mut internal_children = pending
+                .split_off(pending.len() - num_internal_children)
+                .into_iter();
+
+            let children = interval
+                .child_intervals
+                .iter()
+                .map(|child_interval| {
+                    if child_interval.len() == 1 {
+                        leaf(child_interval.start())
+                    } else {
+                        internal_children.next().unwrap()
+                    }
+                })
+                .collect();
+
+            pending.push(node(&interval, children));
+        }
+
+        pending.pop()
+    }
+}
+
+/// Iterator over all LCP intervals in postorder.
+///
+/// See [`lcp_interval`](self) for details.
+#[derive(Debug, Clone)]
+pub struct LcpIntervals<'a, O: OutputElement> {
+    lcp: &'a [O],
+    // the next LCP array index to process, the leaf before it is the most recently finished child
+    next_index: usize,
+    last_child_interval: SuffixArrayInterval,
+    stack: Vec<OpenInterval>,
+}
+
+#[derive(Debug, Clone)]
+struct OpenInterval {
+    lcp: isize,
+    start: usize,
+    child_intervals: Vec<SuffixArrayInterval>,
+}
+
+impl<'a, O: OutputElement> LcpIntervals<'a, O> {
+    fn new(lcp: &'a [O]) -> Self {
+        // the bottom of the stack has a virtual LCP value of -1 and is never reported
+        Self {
+            lcp,
+            next_index: 1,
+            last_child_interval: SuffixArrayInterval::new(0, 1.min(lcp.len())),
+            stack: vec![OpenInterval {
+                lcp: -1,
+                start: 0,
+                child_intervals: Vec::new(),
+            }],
+        }
+    }
+
+    // the LCP array with a virtual value of -1 past the last position
+    fn lcp_value(&self, i: usize) -> isize {
+        if i == self.lcp.len() {
+            -1
+        } else {
+            <isize as NumCast>::from(self.lcp[i]).unwrap()
+        }
+    }
+}
+
+impl<'a, O: OutputElement> Iterator for LcpIntervals<'a, O> {
+    type Item = LcpInterval;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_index <= self.lcp.len() {
+            let lcp = self.lcp_value(self.next_index);
+            let top = self.stack.last_mut().unwrap();
+
+            if lcp < top.lcp {
+                let mut open_interval = self.stack.pop().unwrap();
+                open_interval.child_intervals.push(self.last_child_interval);
+
+                let interval = SuffixArrayInterval::new(open_interval.start, self.next_index);
+                self.last_child_interval = interval;
+
+                return Some(LcpInterval {
+                    lcp: open_interval.lcp as usize,
+                    interval,
+                    child_intervals: open_interval.child_intervals,
+                });
+            }
+
+            if lcp == top.lcp {
+                top.child_intervals.push(self.last_child_interval);
+            } else {
+                self.stack.push(OpenInterval {
+                    lcp,
+                    start: self.last_child_interval.start(),
+                    child_intervals: vec![self.last_child_interval],
+                });
+            }
+
+            self.last_child_interval =
+                SuffixArrayInterval::new(self.next_index, self.next_index + 1);
+            self.next_index += 1;
+        }
+
+        None
+    }
+}
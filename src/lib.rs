@@ -25,6 +25,28 @@
  * * [`unbwt`]: Recover the original text from a BWT.
  * * [`plcp`]: Construct the permuted longest common prefix array (PLCP) for a suffix array and text.
  * * [`lcp`]: Construct the longest common prefix array from a PLCP for a suffix array and text.
+ * * [`search`]: Count and locate the occurrences of patterns using a suffix array.
+ * * [`position_restricted`]: Count and locate the occurrences of patterns that start within a range of text positions.
+ * * [`wildcard`]: Find the occurrences of patterns with character classes and wildcards, such as IUPAC codes.
+ * * [`approximate`]: Find the approximate occurrences of patterns with a bounded Hamming or edit distance.
+ * * [`lcp_interval`]: Traverse the LCP intervals (internal nodes of the virtual suffix tree) bottom-up.
+ * * [`repeats`]: Enumerate maximal and supermaximal repeats and find the longest repeated substrings.
+ * * [`distinct_substrings`]: Count the distinct substrings of a text, in total and for every length.
+ * * [`common_substrings`]: Find the longest substrings that occur in at least `k` of a set of texts, for every `k`.
+ * * [`mum`]: Find maximal unique and maximal exact matches between two texts using their generalized suffix array.
+ * * [`lz77`]: Compute the LZ77 factorization and the longest previous factor array of a text.
+ * * [`lce`]: Answer range minimum queries and longest common extension queries in constant time.
+ * * [`esa`]: Navigate the virtual suffix tree of a text using an enhanced suffix array with child table.
+ * * [`suffix_automaton`]: Build the suffix automaton (DAWG) of a text from its suffix array and LCP array.
+ * * [`matching_statistics`]: Compute the longest match at every position of a query using a suffix automaton.
+ * * [`csa`]: Store a queryable suffix array in a fraction of its memory using a compressed suffix array.
+ * * [`fm_index`]: Count and locate the occurrences of patterns using an FM-index built from a BWT.
+ * * [`bidirectional_fm_index`]: Extend patterns to the left and to the right using the FM-indices of a text and its reverse.
+ * * [`smem`]: Find super-maximal and maximal exact matches of a query using a bidirectional FM-index.
+ * * [`r_index`]: Count and locate the occurrences of patterns in repetitive texts using a run-length compressed BWT.
+ * * [`text_index`]: Query all index types of this crate through a common trait.
+ * * [`bitvec`]: Answer rank and select queries on bit vectors, for example to mark positions of a suffix array or BWT.
+ * * [`wavelet_matrix`]: Answer rank, select and range queries on sequences over large alphabets, such as `u16` BWTs.
  * * [`context`]: Use a memory allocation optimization for repeated calls on small inputs.
  *
  * # Usage
@@ -71,13 +93,35 @@
  * [suffix array]: https://en.wikipedia.org/wiki/Suffix_array
  */
 
+pub mod approximate;
+pub mod bidirectional_fm_index;
+pub mod bitvec;
 pub mod bwt;
+pub mod common_substrings;
 pub mod context;
+pub mod csa;
+pub mod distinct_substrings;
+pub mod esa;
+pub mod fm_index;
+pub mod lce;
 pub mod lcp;
+pub mod lcp_interval;
+pub mod lz77;
+pub mod matching_statistics;
+pub mod mum;
 pub mod plcp;
+pub mod position_restricted;
+pub mod r_index;
+pub mod repeats;
+pub mod search;
+pub mod smem;
 pub mod suffix_array;
+pub mod suffix_automaton;
+pub mod text_index;
 pub mod typestate;
 pub mod unbwt;
+pub mod wavelet_matrix;
+pub mod wildcard;
 
 mod generics_dispatch;
 mod owned_or_borrowed;
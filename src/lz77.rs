@@ -0,0 +1,263 @@
+/*!
+ * Compute the Lempel-Ziv 77 factorization and the longest previous factor array of a text.
+ *
+ * The greedy [LZ77] factorization splits the text into phrases from left to right. Every phrase is either the
+ * longest prefix of the remaining text that also starts at an earlier position, or a single literal symbol if
+ * the next symbol does not occur earlier. The number of phrases `z` is a common measure of the repetitiveness of a
+ * text. The _longest previous factor array_ (LPF) contains for every text position `i` the length of the longest
+ * prefix of the suffix `i` that also starts at an earlier position.
+ *
+ * Both are computed from a [`SuffixArrayWithPlcp`] and the text with the linear-time algorithms of
+ * [Kärkkäinen, Kempa and Puglisi][KKP]. The longest previous factor of a suffix `i` starts at one of two
+ * candidate positions: the previous smaller value (PSV) or the next smaller value (NSV) of `i` in the suffix array,
+ * i.e. the closest suffixes in lexicographical order that start before `i`. The candidates are computed with
+ * [`lce::previous_smaller_values`] and [`lce::next_smaller_values`] in `O(n)` time.
+ *
+ * * [`SuffixArrayWithPlcp::lz77_parse`] compares the text only at the starts of the phrases, which takes `O(n)`
+ *   time overall.
+ * * [`SuffixArrayWithPlcp::lpf_array`] reuses the common prefix length of the previous position, as in the
+ *   construction of the PLCP array, which takes `O(n)` time overall. The PLCP value of a position is an upper bound
+ *   for its common prefix length with the PSV candidate.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, lz77::{self, Phrase}};
+ *
+ * let text = b"abababb".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let parse = res.lz77_parse(text);
+ * assert_eq!(
+ *     parse,
+ *     vec![
+ *         Phrase::Literal(b'a'),
+ *         Phrase::Literal(b'b'),
+ *         Phrase::Copy { source: 0, len: 4 },
+ *         Phrase::Copy { source: 1, len: 1 },
+ *     ]
+ * );
+ * assert_eq!(lz77::decode(&parse), text);
+ *
+ * assert_eq!(res.lpf_array(text), vec![0, 0, 4, 3, 2, 1, 1]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The source of a phrase may overlap the phrase itself, as in the phrase `(0, 4)` of the example. Generalized
+ * suffix arrays are not supported.
+ *
+ * [LZ77]: https://doi.org/10.1109/TIT.1977.1055714
+ * [KKP]: https://doi.org/10.1007/978-3-642-38905-4_21
+ */
+
+use num_traits::NumCast;
+
+use crate::{InputElement, OutputElement, lce, plcp::SuffixArrayWithPlcp, typestate::BufferMode};
+
+/// A phrase of an LZ77 factorization.
+///
+/// See [`lz77`](self) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phrase<I> {
+    /// A symbol that does not occur before the phrase.
+    Literal(I),
+    /// A copy of the `len` symbols that start at the earlier text position `source`.
+    Copy { source: usize, len: usize },
+}
+
+impl<I> Phrase<I> {
+    /// The number of text symbols that the phrase covers.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Literal(_) => 1,
+            Self::Copy { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Recover the text from its LZ77 factorization.
+///
+/// # Panics
+///
+/// If a source of a phrase does not start before the phrase.
+pub fn decode<I: InputElement>(phrases: &[Phrase<I>]) -> Vec<I> {
+    let mut text = Vec::with_capacity(phrases.iter().map(Phrase::len).sum());
+
+    for phrase in phrases {
+        match *phrase {
+            Phrase::Literal(symbol) => text.push(symbol),
+            Phrase::Copy { source, len } => {
+                assert!(
+                    source < text.len(),
+                    "The source must start before the phrase"
+                );
+
+                // the source may overlap the phrase, so the symbols are copied one by one
+                for i in source..source + len {
+                    text.push(text[i]);
+                }
+            }
+        }
+    }
+
+    text
+}
+
+impl<'p, 's, O: OutputElement, SaB: BufferMode, PlcpB: BufferMode>
+    SuffixArrayWithPlcp<'p, 's, O, SaB, PlcpB>
+{
+    /// The greedy LZ77 factorization of the text. The number of phrases is the LZ77 measure `z`.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the suffix array is a generalized suffix array or if the text does not have the same length as the
+    /// suffix array.
+    pub fn lz77_parse<I: InputElement>(&self, text: &[I]) -> Vec<Phrase<I>> {
+        let candidate_sources = self.candidate_sources(text);
+        let mut phrases = Vec::new();
+        let mut position = 0;
+
+        while position < text.len() {
+            let (source, len) = candidate_sources[position]
+                .into_iter()
+                .flatten()
+                .map(|source| {
+                    let len = common_prefix_len(text, source, position, 0, text.len());
+                    (source, len)
+                })
+                .fold((0, 0), |best, candidate| {
+                    if candidate.1 > best.1 {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+
+            if len == 0 {
+                phrases.push(Phrase::Literal(text[position]));
+                position += 1;
+            } else {
+                phrases.push(Phrase::Copy { source, len });
+                position += len;
+            }
+        }
+
+        phrases
+    }
+
+    /// The longest previous factor array of the text.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the suffix array is a generalized suffix array or if the text does not have the same length as the
+    /// suffix array.
+    pub fn lpf_array<I: InputElement>(&self, text: &[I]) -> Vec<usize> {
+        let candidate_sources = self.candidate_sources(text);
+        let plcp = self.plcp();
+
+        let mut lpf = Vec::with_capacity(text.len());
+        let mut previous_len: usize = 0;
+        let mut next_len: usize = 0;
+
+        // like the PLCP, the common prefix lengths with the candidates decrease by at most one per position
+        for (position, &plcp_value) in plcp.iter().enumerate() {
+            let [previous_source, next_source] = candidate_sources[position];
+
+            previous_len = previous_source.map_or(0, |source| {
+                let max_len = <usize as NumCast>::from(plcp_value).unwrap();
+                common_prefix_len(
+                    text,
+                    source,
+                    position,
+                    previous_len.saturating_sub(1),
+                    max_len,
+                )
+            });
+            next_len = next_source.map_or(0, |source| {
+                common_prefix_len(
+                    text,
+                    source,
+                    position,
+                    next_len.saturating_sub(1),
+                    text.len(),
+                )
+            });
+
+            lpf.push(previous_len.max(next_len));
+        }
+
+        lpf
+    }
+
+    // for every text position, the text positions of its previous and next smaller values in the suffix array
+    fn candidate_sources<I: InputElement>(&self, text: &[I]) -> Vec<[Option<usize>; 2]> {
+        assert!(
+            !self.is_generalized_suffix_array(),
+            "LZ77 does not support generalized suffix arrays"
+        );
+        assert_eq!(
+            text.len(),
+            self.suffix_array().len(),
+            "The text must have the same length as the suffix array"
+        );
+
+        let suffix_array = self.suffix_array();
+        let previous_smaller_values = lce::previous_smaller_values(suffix_array);
+        let next_smaller_values = lce::next_smaller_values(suffix_array);
+
+        // the smaller values are suffix array indices, -1 and n mean that there is none
+        let source = |suffix_array_index: O| {
+            <usize as NumCast>::from(suffix_array_index)
+                .filter(|&suffix_array_index| suffix_array_index < suffix_array.len())
+                .map(|suffix_array_index| {
+                    <usize as NumCast>::from(suffix_array[suffix_array_index]).unwrap()
+                })
+        };
+
+        let mut candidate_sources = vec![[None; 2]; text.len()];
+
+        for (suffix_array_index, &suffix) in suffix_array.iter().enumerate() {
+            candidate_sources[<usize as NumCast>::from(suffix).unwrap()] = [
+                source(previous_smaller_values[suffix_array_index]),
+                source(next_smaller_values[suffix_array_index]),
+            ];
+        }
+
+        candidate_sources
+    }
+}
+
+// the common prefix length of the suffixes source and position, of which the first known_len symbols are equal
+// and which is at most max_len
+fn common_prefix_len<I: InputElement>(
+    text: &[I],
+    source: usize,
+    position: usize,
+    known_len: usize,
+    max_len: usize,
+) -> usize {
+    let mut len = known_len;
+
+    while len < max_len && position + len < text.len() && text[source + len] == text[position + len]
+    {
+        len += 1;
+    }
+
+    len
+}
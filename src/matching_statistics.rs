@@ -0,0 +1,98 @@
+/*!
+ * Compute the matching statistics of a query against a text using a [`SuffixAutomaton`].
+ *
+ * The matching statistics of a query `Q` contain for every position `i` of the query the length of the longest
+ * prefix of `Q[i..]` that is a substring of the text, together with the suffix array interval of the occurrences
+ * of this prefix. They are the basis of many alignment-free methods, such as the classification of sequencing
+ * reads or the computation of distances between sequences.
+ *
+ * The suffix automaton reads the query from right to left. When the current match cannot be extended by the next
+ * symbol, it is shortened by following suffix links. Therefore, the matching statistics of a query of length `m`
+ * are computed in `O(m)` transitions, independent of the lengths of the matches.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, suffix_automaton::SuffixAutomaton};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let automaton = SuffixAutomaton::new(&res, text);
+ * let matching_statistics = automaton.matching_statistics(b"cabrab");
+ *
+ * let lens: Vec<_> = matching_statistics.iter().map(|statistic| statistic.len()).collect();
+ * assert_eq!(lens, vec![2, 4, 3, 2, 2, 1]);
+ *
+ * // "abra" occurs at text positions 0 and 7
+ * assert_eq!(matching_statistics[1].interval().len(), 2);
+ * assert_eq!(matching_statistics[1].text_position(), Some(7));
+ * ```
+ *
+ * [`SuffixAutomaton`]: super::suffix_automaton::SuffixAutomaton
+ */
+
+use crate::{InputElement, search::SuffixArrayInterval, suffix_automaton::SuffixAutomaton};
+
+/// The longest match that starts at a position of a query.
+///
+/// See [`matching_statistics`](self) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MatchingStatistic {
+    len: usize,
+    interval: SuffixArrayInterval,
+    text_position: usize,
+}
+
+impl MatchingStatistic {
+    /// The length of the longest prefix of the query suffix that is a substring of the text.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The suffix array interval of the occurrences of the match. For an empty match, this is the interval of
+    /// the whole suffix array.
+    pub fn interval(&self) -> SuffixArrayInterval {
+        self.interval
+    }
+
+    /// One text position at which the match occurs, which is the suffix array entry at the start of its
+    /// interval. It is `None` for an empty match.
+    pub fn text_position(&self) -> Option<usize> {
+        (self.len > 0).then_some(self.text_position)
+    }
+}
+
+impl<I: InputElement> SuffixAutomaton<I> {
+    /// The matching statistics of `query`, one for every position of the query.
+    pub fn matching_statistics(&self, query: &[I]) -> Vec<MatchingStatistic> {
+        let mut matching_statistics = Vec::with_capacity(query.len());
+
+        self.for_each_longest_match(query, |_, state, match_len| {
+            matching_statistics.push(MatchingStatistic {
+                len: match_len,
+                interval: self.interval(state),
+                text_position: self.text_position(state),
+            });
+        });
+
+        // the query was scanned from right to left
+        matching_statistics.reverse();
+        matching_statistics
+    }
+}
@@ -0,0 +1,366 @@
+/*!
+ * Find maximal unique matches (MUMs) and maximal exact matches (MEMs) between two texts using their generalized
+ * suffix array.
+ *
+ * A _maximal exact match_ is a triple `(pos_a, pos_b, len)` such that `a[pos_a..pos_a + len]` equals
+ * `b[pos_b..pos_b + len]` and the match can be extended neither to the left nor to the right. A _maximal unique
+ * match_ is a maximal exact match whose substring occurs exactly once in each of the two texts. MUMs are the
+ * anchors of whole-genome alignment tools like [MUMmer].
+ *
+ * [`maximal_unique_matches`] and [`maximal_exact_matches`] construct the generalized suffix array of the two
+ * texts with [`SuffixArrayConstruction::generalized_suffix_array`], the PLCP array and the LCP array. The matches
+ * are then found by a traversal of the LCP intervals:
+ *
+ * * MUMs are LCP intervals that contain exactly two suffixes, one of each text, whose preceding symbols differ.
+ *   They are found in `O(n)` time.
+ * * MEMs are pairs of suffixes of different texts in different child intervals of an LCP interval, whose
+ *   preceding symbols differ. As in the algorithm of [Abouelhoda, Kurtz and Ohlebusch][AKO], the suffixes of every
+ *   subtree are partitioned by their preceding symbol into linked lists, which are concatenated in constant time.
+ *   Only the lists with different preceding symbols are combined, so every enumerated pair is a MEM. For an
+ *   alphabet of size `σ`, this takes `O(σ² n + z)` time, where `z` is the number of MEMs.
+ *
+ * ```
+ * use libsais::mum;
+ *
+ * let a = b"GATTACAGATTACA".as_slice();
+ * let b = b"TTACAGGATTAC".as_slice();
+ *
+ * let mums = mum::maximal_unique_matches(a, b, 4).unwrap();
+ *
+ * assert_eq!(mums.len(), 1);
+ * assert_eq!(
+ *     (mums[0].position_a(), mums[0].position_b(), mums[0].len()),
+ *     (2, 0, 6)
+ * );
+ *
+ * let mems = mum::maximal_exact_matches(a, b, 5).unwrap();
+ * assert_eq!(mems.len(), 4);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The texts must not contain zero symbols, because zeros are used as the separators of the generalized suffix
+ * array. The start and the end of each text behave like unique symbols, so a match that starts at the start of
+ * one of the texts is always left maximal.
+ *
+ * [MUMmer]: https://doi.org/10.1371/journal.pcbi.1005944
+ * [AKO]: https://doi.org/10.1016/S1570-8667(03)00065-0
+ * [`SuffixArrayConstruction::generalized_suffix_array`]: crate::SuffixArrayConstruction::generalized_suffix_array
+ */
+
+use num_traits::NumCast;
+
+use crate::{
+    LIBSAIS_I32_OUTPUT_MAXIMUM_SIZE, LibsaisError, OutputElement, SmallAlphabet,
+    SuffixArrayConstruction, SupportsPlcpOutputFor, lcp::SuffixArrayWithLcpAndPlcp,
+    typestate::BufferMode,
+};
+
+/// A maximal match between a position of the first text and a position of the second text.
+///
+/// See [`mum`](self) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaximalMatch {
+    position_a: usize,
+    position_b: usize,
+    len: usize,
+}
+
+impl MaximalMatch {
+    pub fn position_a(&self) -> usize {
+        self.position_a
+    }
+
+    pub fn position_b(&self) -> usize {
+        self.position_b
+    }
+
+    /// The length of the match.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// All MUMs of `a` and `b` with a length of at least `min_len`, sorted by their positions in `a`.
+///
+/// # Panics
+///
+/// If one of the texts contains a zero symbol.
+pub fn maximal_unique_matches<I: SmallAlphabet>(
+    a: &[I],
+    b: &[I],
+    min_len: usize,
+) -> Result<Vec<MaximalMatch>, LibsaisError>
+where
+    i32: SupportsPlcpOutputFor<I>,
+    i64: SupportsPlcpOutputFor<I>,
+{
+    find_maximal_matches(a, b, min_len, MatchKind::Unique)
+}
+
+/// All MEMs of `a` and `b` with a length of at least `min_len`, sorted by their positions in `a` and `b`.
+///
+/// # Panics
+///
+/// If one of the texts contains a zero symbol.
+pub fn maximal_exact_matches<I: SmallAlphabet>(
+    a: &[I],
+    b: &[I],
+    min_len: usize,
+) -> Result<Vec<MaximalMatch>, LibsaisError>
+where
+    i32: SupportsPlcpOutputFor<I>,
+    i64: SupportsPlcpOutputFor<I>,
+{
+    find_maximal_matches(a, b, min_len, MatchKind::Exact)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatchKind {
+    Unique,
+    Exact,
+}
+
+fn find_maximal_matches<I: SmallAlphabet>(
+    a: &[I],
+    b: &[I],
+    min_len: usize,
+    kind: MatchKind,
+) -> Result<Vec<MaximalMatch>, LibsaisError>
+where
+    i32: SupportsPlcpOutputFor<I>,
+    i64: SupportsPlcpOutputFor<I>,
+{
+    assert!(
+        !a.contains(&I::zero()) && !b.contains(&I::zero()),
+        "The texts must not contain zero symbols"
+    );
+
+    let mut text = Vec::with_capacity(a.len() + b.len() + 2);
+    text.extend_from_slice(a);
+    text.push(I::zero());
+    text.extend_from_slice(b);
+    text.push(I::zero());
+
+    let mut matches = if text.len() <= LIBSAIS_I32_OUTPUT_MAXIMUM_SIZE {
+        let res = SuffixArrayConstruction::for_text(&text)
+            .in_owned_buffer32()
+            .single_threaded()
+            .generalized_suffix_array()
+            .run()?
+            .plcp_construction()
+            .single_threaded()
+            .run()?
+            .lcp_construction()
+            .single_threaded()
+            .run()?;
+
+        gsa_matches(&res, &text, a.len(), min_len, kind)
+    } else {
+        let res = SuffixArrayConstruction::for_text(&text)
+            .in_owned_buffer64()
+            .single_threaded()
+            .generalized_suffix_array()
+            .run()?
+            .plcp_construction()
+            .single_threaded()
+            .run()?
+            .lcp_construction()
+            .single_threaded()
+            .run()?;
+
+        gsa_matches(&res, &text, a.len(), min_len, kind)
+    };
+
+    matches.sort_unstable();
+
+    Ok(matches)
+}
+
+fn gsa_matches<I, O, SaB, LcpB, PlcpB>(
+    index: &SuffixArrayWithLcpAndPlcp<'_, '_, '_, O, SaB, LcpB, PlcpB>,
+    text: &[I],
+    len_a: usize,
+    min_len: usize,
+    kind: MatchKind,
+) -> Vec<MaximalMatch>
+where
+    I: SmallAlphabet,
+    O: OutputElement,
+    SaB: BufferMode,
+    LcpB: BufferMode,
+    PlcpB: BufferMode,
+{
+    let min_len = min_len.max(1);
+    let suffix = |suffix_array_index: usize| {
+        <usize as NumCast>::from(index.suffix_array()[suffix_array_index]).unwrap()
+    };
+
+    // the separator before the second text is unique as well
+    let preceding_symbol = |suffix: usize| {
+        suffix
+            .checked_sub(1)
+            .map(|i| text[i])
+            .filter(|&symbol| symbol != I::zero())
+    };
+
+    // the match of two suffixes, if they belong to different texts and the match is left maximal
+    let maximal_match = |suffix: usize, other_suffix: usize, len: usize| {
+        let (position_a, position_b) = match (suffix < len_a, other_suffix < len_a) {
+            (true, false) => (suffix, other_suffix),
+            (false, true) => (other_suffix, suffix),
+            _ => return None,
+        };
+
+        let is_left_maximal = preceding_symbol(position_a).is_none()
+            || preceding_symbol(position_a) != preceding_symbol(position_b);
+
+        is_left_maximal.then_some(MaximalMatch {
+            position_a,
+            position_b: position_b - len_a - 1,
+            len,
+        })
+    };
+
+    match kind {
+        MatchKind::Unique => index
+            .lcp_intervals()
+            .filter(|lcp_interval| {
+                lcp_interval.lcp() >= min_len && lcp_interval.interval().len() == 2
+            })
+            .filter_map(|lcp_interval| {
+                let start = lcp_interval.interval().start();
+                maximal_match(suffix(start), suffix(start + 1), lcp_interval.lcp())
+            })
+            .collect(),
+        MatchKind::Exact => {
+            let mut matches = Vec::new();
+            let mut next_positions = vec![NO_POSITION; text.len()];
+
+            // the suffixes of a subtree partitioned by left context, or nothing if the LCP value of
+            // its root is too small to report any match above it
+            index.fold_lcp_intervals(
+                |suffix_array_index| {
+                    let suffix = suffix(suffix_array_index);
+                    let mut positions = [PositionList::EMPTY; 2];
+                    positions[(suffix >= len_a) as usize] = PositionList {
+                        head: suffix,
+                        tail: suffix,
+                    };
+
+                    vec![LeftContextClass {
+                        left_context: preceding_symbol(suffix),
+                        positions,
+                    }]
+                },
+                |lcp_interval, children: Vec<Vec<LeftContextClass<I>>>| {
+                    if lcp_interval.lcp() < min_len {
+                        return Vec::new();
+                    }
+
+                    let mut children = children.into_iter();
+                    let mut classes = children.next().unwrap();
+
+                    // suffixes of different children diverge after exactly lcp symbols, so only
+                    // pairs with different left contexts or at the start of a text are reported
+                    for child in children {
+                        for child_class in &child {
+                            for class in &classes {
+                                if child_class.left_context.is_some()
+                                    && child_class.left_context == class.left_context
+                                {
+                                    continue;
+                                }
+
+                                for (a_list, b_list) in [
+                                    (child_class.positions[0], class.positions[1]),
+                                    (class.positions[0], child_class.positions[1]),
+                                ] {
+                                    for position_a in a_list.iter(&next_positions) {
+                                        matches.extend(b_list.iter(&next_positions).map(
+                                            |position_b| MaximalMatch {
+                                                position_a,
+                                                position_b: position_b - len_a - 1,
+                                                len: lcp_interval.lcp(),
+                                            },
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+
+                        for child_class in child {
+                            match classes
+                                .iter_mut()
+                                .find(|class| class.left_context == child_class.left_context)
+                            {
+                                Some(class) => {
+                                    for (list, child_list) in
+                                        class.positions.iter_mut().zip(child_class.positions)
+                                    {
+                                        list.append(child_list, &mut next_positions);
+                                    }
+                                }
+                                None => classes.push(child_class),
+                            }
+                        }
+                    }
+
+                    classes
+                },
+            );
+
+            matches
+        }
+    }
+}
+
+// the end of a linked list of text positions
+const NO_POSITION: usize = usize::MAX;
+
+// a linked list of text positions, whose links are stored in a shared array, such that two lists
+// can be concatenated in constant time
+#[derive(Debug, Clone, Copy)]
+struct PositionList {
+    head: usize,
+    tail: usize,
+}
+
+impl PositionList {
+    const EMPTY: Self = Self {
+        head: NO_POSITION,
+        tail: NO_POSITION,
+    };
+
+    fn append(&mut self, other: Self, next_positions: &mut [usize]) {
+        if other.head == NO_POSITION {
+            return;
+        }
+
+        if self.head == NO_POSITION {
+            *self = other;
+        } else {
+            next_positions[self.tail] = other.head;
+            self.tail = other.tail;
+        }
+    }
+
+    fn iter(self, next_positions: &[usize]) -> impl Iterator<Item = usize> {
+        std::iter::successors(
+            Some(self.head).filter(|&position| position != NO_POSITION),
+            |&position| Some(next_positions[position]).filter(|&position| position != NO_POSITION),
+        )
+    }
+}
+
+// the suffixes of a subtree with the same left context, the first list contains the suffixes of the
+// first text and the second list those of the second text
+#[derive(Debug)]
+struct LeftContextClass<I> {
+    left_context: Option<I>,
+    positions: [PositionList; 2],
+}
@@ -0,0 +1,139 @@
+/*!
+ * Count and locate the occurrences of patterns that start within a range of text positions.
+ *
+ * The [`PositionRestrictedIndex`] stores a [`WaveletMatrix`] over the suffix array. After the suffix array
+ * interval of a pattern is found by binary search, the wavelet matrix is used to answer two-dimensional range
+ * queries on the interval and the text range:
+ *
+ * * [`PositionRestrictedIndex::count`] needs `O(log n)` time after the binary search, independent of the number
+ *   of occurrences of the pattern.
+ * * [`PositionRestrictedIndex::locate`] needs `O(log n)` time per reported occurrence and reports the
+ *   occurrences in ascending order of their text positions, without looking at the occurrences outside of the
+ *   text range.
+ *
+ * The wavelet matrix needs `n * ⌈log n⌉` bits plus the rank information, in addition to the suffix array.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, position_restricted::PositionRestrictedIndex};
+ *
+ * let text = b"abracadabra-abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let index = PositionRestrictedIndex::new(&res);
+ *
+ * assert_eq!(index.count(b"abra", 0..text.len()), 4);
+ * assert_eq!(index.count(b"abra", 5..15), 2);
+ * assert_eq!(index.locate(b"abra", 5..15), vec![7, 12]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * Like in the [`search`](super::search) module, the end of the text compares smaller than every symbol and zero
+ * symbols in the pattern are compared like any other symbol. Only the start of an occurrence is restricted to
+ * the text range, an occurrence may end after the end of the range.
+ */
+
+use std::ops::Range;
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement,
+    search::{self, SuffixArrayInterval},
+    suffix_array::SuffixArrayWithText,
+    typestate::BufferMode,
+    wavelet_matrix::WaveletMatrix,
+};
+
+/// An index for occurrences of patterns that start within a range of text positions.
+///
+/// See [`position_restricted`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PositionRestrictedIndex<'a, I: InputElement, O: OutputElement> {
+    text: &'a [I],
+    suffix_array: &'a [O],
+    suffix_array_matrix: WaveletMatrix<O>,
+}
+
+impl<'a, I: InputElement, O: OutputElement> PositionRestrictedIndex<'a, I, O> {
+    /// Build the wavelet matrix over the suffix array of `index`.
+    pub fn new<B: BufferMode>(index: &'a SuffixArrayWithText<'_, '_, I, O, B>) -> Self {
+        Self {
+            text: index.text,
+            suffix_array: &index.suffix_array.buffer,
+            suffix_array_matrix: WaveletMatrix::new(&index.suffix_array.buffer),
+        }
+    }
+
+    pub fn text(&self) -> &[I] {
+        self.text
+    }
+
+    pub fn suffix_array(&self) -> &[O] {
+        self.suffix_array
+    }
+
+    /// The length of the text.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The suffix array interval of all suffixes that start with `pattern`, regardless of their position.
+    pub fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        search::interval(self.text, self.suffix_array, pattern)
+    }
+
+    /// The number of occurrences of `pattern` that start within `text_range`.
+    ///
+    /// # Panics
+    ///
+    /// If the start of the text range is after its end.
+    pub fn count(&self, pattern: &[I], text_range: Range<usize>) -> usize {
+        let interval = self.interval(pattern);
+        let (start, end) = self.value_bounds(text_range);
+
+        self.suffix_array_matrix
+            .count_less_than(end, interval.start(), interval.end())
+            - self
+                .suffix_array_matrix
+                .count_less_than(start, interval.start(), interval.end())
+    }
+
+    /// The positions of the occurrences of `pattern` that start within `text_range`, sorted by text position.
+    ///
+    /// # Panics
+    ///
+    /// If the start of the text range is after its end.
+    pub fn locate(&self, pattern: &[I], text_range: Range<usize>) -> Vec<usize> {
+        let interval = self.interval(pattern);
+        let (start, end) = self.value_bounds(text_range);
+
+        self.suffix_array_matrix
+            .distinct_in_value_range(interval.start(), interval.end(), start..end)
+            .into_iter()
+            .map(|(suffix, _)| <usize as NumCast>::from(suffix).unwrap())
+            .collect()
+    }
+
+    // the text range clamped to the text, converted to the output element type
+    fn value_bounds(&self, text_range: Range<usize>) -> (O, O) {
+        assert!(
+            text_range.start <= text_range.end,
+            "Start of the text range cannot be after its end"
+        );
+
+        let to_value =
+            |text_position: usize| <O as NumCast>::from(text_position.min(self.len())).unwrap();
+
+        (to_value(text_range.start), to_value(text_range.end))
+    }
+}
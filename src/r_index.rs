@@ -0,0 +1,472 @@
+/*!
+ * Count and locate patterns in highly repetitive texts using a run-length compressed BWT and the [r-index].
+ *
+ * The BWT of a repetitive text consists of few runs of equal symbols. The [`RunLengthBwt`] stores only the
+ * symbol, the start and the rank information of every run. Its size therefore depends on the number of runs `r`
+ * instead of the length of the text. The number of runs is a common measure of the repetitiveness of a text and
+ * can be obtained via [`RunLengthBwt::num_runs`].
+ *
+ * The [`RIndex`] adds the suffix array entries at the ends of the runs and the data for the `φ` function, which
+ * maps a suffix array entry to its predecessor in the suffix array. During backward search, the index keeps
+ * track of the suffix array entry of the last row of the current interval (the "toehold"). All other occurrences are
+ * then found by repeatedly applying `φ`. The whole index needs `O(r)` words of space.
+ *
+ * ```
+ * use libsais::{BwtConstruction, r_index::{RIndex, RunLengthBwt}};
+ *
+ * let text = b"abracadabra-abracadabra-abracadabra".as_slice();
+ *
+ * let bwt = BwtConstruction::for_text(text)
+ *     .with_owned_temporary_array_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let run_length_bwt = RunLengthBwt::new(&bwt);
+ * assert!(run_length_bwt.num_runs() < text.len());
+ *
+ * let index = RIndex::new(run_length_bwt);
+ * assert_eq!(index.count(b"abra"), 6);
+ * assert_eq!(index.locate_sorted(b"cad"), vec![4, 16, 28]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * In contrast to the [`bwt`](super::bwt) returned by `libsais`, the runs of the [`RunLengthBwt`] are the runs
+ * of the BWT with the sentinel reinserted at the primary index, which is the convention of the literature. The
+ * sentinel always forms a run of length one. The positions of the runs are rows of the BWT matrix, which has
+ * `n + 1` rows for a text of length `n`. Row 0 belongs to the suffix that consists only of the sentinel.
+ *
+ * The intervals returned by the [`RIndex`] follow the convention of the [`search`](super::search) module, i.e. they
+ * are intervals of the suffix array without the entry for the sentinel.
+ *
+ * [r-index]: https://doi.org/10.1145/3375890
+ */
+
+use std::ops::Range;
+
+use crate::{
+    OutputElement, SmallAlphabet,
+    bwt::{Bwt, BwtWithAuxIndices},
+    fm_index::{extract_with_lf, symbol_index},
+    search::SuffixArrayInterval,
+    typestate::BufferMode,
+};
+
+/// A run of equal symbols in the BWT with reinserted sentinel.
+///
+/// The symbol is `None` for the sentinel run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BwtRun<I: SmallAlphabet> {
+    pub start: usize,
+    pub len: usize,
+    pub symbol: Option<I>,
+}
+
+/// A run-length compressed BWT for `u8`/`u16`-based texts.
+///
+/// See [`r_index`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunLengthBwt<I: SmallAlphabet> {
+    text_len: usize,
+    primary_index: usize,
+    // has one additional entry at the end that marks the end of the last run
+    run_starts: Vec<usize>,
+    run_symbols: Vec<I>,
+    // number of occurrences of the symbol of a run before the run
+    run_ranks: Vec<usize>,
+    // run indices of all runs of a symbol c are stored in symbol_runs[symbol_offsets[c]..symbol_offsets[c + 1]]
+    symbol_offsets: Vec<usize>,
+    symbol_runs: Vec<usize>,
+    // counts[c] is the first row of the BWT matrix that starts with c, including the sentinel row
+    counts: Vec<usize>,
+}
+
+impl<I: SmallAlphabet> RunLengthBwt<I> {
+    pub fn new<B: BufferMode>(bwt: &Bwt<'_, I, B>) -> Self {
+        Self::from_bwt_and_primary_index(bwt.bwt(), bwt.primary_index())
+    }
+
+    /// Create the run-length compressed BWT from a BWT with auxiliary indices. Only the primary index is used.
+    pub fn from_aux_indices<O: OutputElement, BwtB: BufferMode, AuxB: BufferMode>(
+        bwt: &BwtWithAuxIndices<'_, '_, I, O, BwtB, AuxB>,
+    ) -> Self {
+        Self::from_bwt_and_primary_index(bwt.bwt(), bwt.primary_index())
+    }
+
+    fn from_bwt_and_primary_index(bwt: &[I], primary_index: usize) -> Self {
+        let mut run_starts = Vec::new();
+        let mut run_symbols = Vec::new();
+        let mut run_ranks = Vec::new();
+        let mut frequencies = vec![0; I::FREQUENCY_TABLE_SIZE];
+
+        // the sentinel is the symbol of row primary_index, the other rows are shifted by one
+        let rows = (0..=bwt.len()).map(|row| {
+            if row < primary_index {
+                Some(bwt[row])
+            } else if row == primary_index {
+                None
+            } else {
+                Some(bwt[row - 1])
+            }
+        });
+
+        let mut previous_symbol = None;
+
+        for (row, symbol) in rows.enumerate() {
+            // the sentinel always forms its own run
+            if row == 0 || symbol.is_none() || symbol != previous_symbol {
+                run_starts.push(row);
+                run_symbols.push(symbol.unwrap_or(I::zero()));
+                run_ranks.push(symbol.map_or(0, |symbol| frequencies[symbol_index(symbol)]));
+            }
+
+            if let Some(symbol) = symbol {
+                frequencies[symbol_index(symbol)] += 1;
+            }
+
+            previous_symbol = symbol;
+        }
+
+        run_starts.push(bwt.len() + 1);
+
+        let sentinel_run = run_starts.partition_point(|&start| start <= primary_index) - 1;
+
+        let mut symbol_offsets = vec![0; I::FREQUENCY_TABLE_SIZE + 1];
+        for (run, &symbol) in run_symbols.iter().enumerate() {
+            if run != sentinel_run {
+                symbol_offsets[symbol_index(symbol) + 1] += 1;
+            }
+        }
+        for symbol in 0..I::FREQUENCY_TABLE_SIZE {
+            symbol_offsets[symbol + 1] += symbol_offsets[symbol];
+        }
+
+        let mut next_positions = symbol_offsets.clone();
+        let mut symbol_runs = vec![0; run_symbols.len() - 1];
+        for (run, &symbol) in run_symbols.iter().enumerate() {
+            if run != sentinel_run {
+                let next_position = &mut next_positions[symbol_index(symbol)];
+                symbol_runs[*next_position] = run;
+                *next_position += 1;
+            }
+        }
+
+        let mut counts = Vec::with_capacity(I::FREQUENCY_TABLE_SIZE + 1);
+        let mut count = 1;
+        for frequency in frequencies {
+            counts.push(count);
+            count += frequency;
+        }
+        counts.push(count);
+
+        Self {
+            text_len: bwt.len(),
+            primary_index,
+            run_starts,
+            run_symbols,
+            run_ranks,
+            symbol_offsets,
+            symbol_runs,
+            counts,
+        }
+    }
+
+    /// The length of the text, which is one less than the number of rows of the BWT matrix.
+    pub fn len(&self) -> usize {
+        self.text_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text_len == 0
+    }
+
+    pub fn primary_index(&self) -> usize {
+        self.primary_index
+    }
+
+    /// The number of runs `r`, including the run of the sentinel.
+    pub fn num_runs(&self) -> usize {
+        self.run_symbols.len()
+    }
+
+    /// The rows at which the runs start, in ascending order.
+    pub fn run_starts(&self) -> &[usize] {
+        &self.run_starts[..self.num_runs()]
+    }
+
+    pub fn runs(&self) -> impl Iterator<Item = BwtRun<I>> + '_ {
+        (0..self.num_runs()).map(|run| BwtRun {
+            start: self.run_starts[run],
+            len: self.run_starts[run + 1] - self.run_starts[run],
+            symbol: self.run_symbol(run),
+        })
+    }
+
+    /// The symbol of the BWT with reinserted sentinel at `row`. The sentinel is represented by `None`.
+    ///
+    /// # Panics
+    ///
+    /// If the row is out of bounds.
+    pub fn access(&self, row: usize) -> Option<I> {
+        assert!(row <= self.text_len, "Row out of bounds");
+        self.run_symbol(self.run_of_row(row))
+    }
+
+    /// The number of occurrences of `symbol` in the BWT with reinserted sentinel before `row`.
+    ///
+    /// # Panics
+    ///
+    /// If the row is out of bounds.
+    pub fn rank(&self, symbol: I, row: usize) -> usize {
+        assert!(row <= self.text_len + 1, "Row out of bounds");
+
+        match self.last_run_of_symbol_before(symbol, row) {
+            Some(run) => {
+                let run_start = self.run_starts[run];
+                let run_end = self.run_starts[run + 1].min(row);
+                self.run_ranks[run] + run_end - run_start
+            }
+            None => 0,
+        }
+    }
+
+    /// Decompress the BWT into the convention of `libsais`, without the sentinel.
+    pub fn to_bwt(&self) -> Vec<I> {
+        let mut bwt = Vec::with_capacity(self.text_len);
+
+        for run in self.runs() {
+            if let Some(symbol) = run.symbol {
+                bwt.extend(std::iter::repeat_n(symbol, run.len));
+            }
+        }
+
+        bwt
+    }
+
+    fn run_symbol(&self, run: usize) -> Option<I> {
+        if self.run_starts[run] == self.primary_index {
+            None
+        } else {
+            Some(self.run_symbols[run])
+        }
+    }
+
+    fn run_of_row(&self, row: usize) -> usize {
+        self.run_starts.partition_point(|&start| start <= row) - 1
+    }
+
+    // the last run with the given symbol that starts before row
+    fn last_run_of_symbol_before(&self, symbol: I, row: usize) -> Option<usize> {
+        let symbol = symbol_index(symbol);
+        let runs = &self.symbol_runs[self.symbol_offsets[symbol]..self.symbol_offsets[symbol + 1]];
+        let num_runs_before = runs.partition_point(|&run| self.run_starts[run] < row);
+
+        num_runs_before.checked_sub(1).map(|i| runs[i])
+    }
+
+    pub(crate) fn lf(&self, row: usize) -> usize {
+        match self.access(row) {
+            Some(symbol) => self.counts[symbol_index(symbol)] + self.rank(symbol, row),
+            None => 0,
+        }
+    }
+
+    pub(crate) fn lf_interval(
+        &self,
+        start_row: usize,
+        end_row: usize,
+        symbol: I,
+    ) -> (usize, usize) {
+        let count = self.counts[symbol_index(symbol)];
+
+        (
+            count + self.rank(symbol, start_row),
+            count + self.rank(symbol, end_row),
+        )
+    }
+}
+
+/// An r-index for `u8`/`u16`-based texts.
+///
+/// See [`r_index`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RIndex<I: SmallAlphabet> {
+    run_length_bwt: RunLengthBwt<I>,
+    // the text position of the suffix at the last row of every run
+    run_end_samples: Vec<usize>,
+    // sorted text positions of the suffixes at the first rows of the runs (except row 0), and the
+    // text positions of the suffixes in the rows directly before them
+    phi_keys: Vec<usize>,
+    phi_values: Vec<usize>,
+}
+
+impl<I: SmallAlphabet> RIndex<I> {
+    /// Build the index by sampling the suffix array at the run boundaries.
+    ///
+    /// The samples are computed by applying the LF mapping for every position of the text.
+    pub fn new(run_length_bwt: RunLengthBwt<I>) -> Self {
+        let num_runs = run_length_bwt.num_runs();
+        let mut run_start_samples = vec![0; num_runs];
+        let mut run_end_samples = vec![0; num_runs];
+
+        let mut row = 0;
+        let mut text_position = run_length_bwt.len();
+
+        loop {
+            let run = run_length_bwt.run_of_row(row);
+
+            if run_length_bwt.run_starts[run] == row {
+                run_start_samples[run] = text_position;
+            }
+
+            if run_length_bwt.run_starts[run + 1] == row + 1 {
+                run_end_samples[run] = text_position;
+            }
+
+            if text_position == 0 {
+                break;
+            }
+
+            row = run_length_bwt.lf(row);
+            text_position -= 1;
+        }
+
+        let mut phi_samples: Vec<_> = (1..num_runs)
+            .map(|run| (run_start_samples[run], run_end_samples[run - 1]))
+            .collect();
+        phi_samples.sort_unstable();
+
+        let (phi_keys, phi_values) = phi_samples.into_iter().unzip();
+
+        Self {
+            run_length_bwt,
+            run_end_samples,
+            phi_keys,
+            phi_values,
+        }
+    }
+
+    pub fn run_length_bwt(&self) -> &RunLengthBwt<I> {
+        &self.run_length_bwt
+    }
+
+    /// The length of the indexed text.
+    pub fn len(&self) -> usize {
+        self.run_length_bwt.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.run_length_bwt.is_empty()
+    }
+
+    /// The suffix array interval of all suffixes that start with `pattern`, found via backward search.
+    pub fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        let (start_row, end_row, _) = self.interval_rows_with_toehold(pattern);
+
+        if pattern.is_empty() {
+            SuffixArrayInterval::new(0, self.len())
+        } else {
+            // the rows are shifted by one compared to the suffix array because of the sentinel row
+            SuffixArrayInterval::new(start_row.max(1) - 1, end_row.max(1) - 1)
+        }
+    }
+
+    /// The number of occurrences of `pattern` in the text.
+    pub fn count(&self, pattern: &[I]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    pub fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        let (mut start_row, end_row, toehold) = self.interval_rows_with_toehold(pattern);
+
+        // the sentinel row is not part of the suffix array
+        start_row = start_row.max(1);
+
+        if start_row >= end_row {
+            return Vec::new();
+        }
+
+        let mut positions = Vec::with_capacity(end_row - start_row);
+        let mut text_position = toehold;
+        positions.push(text_position);
+
+        for _ in start_row + 1..end_row {
+            text_position = self.phi(text_position);
+            positions.push(text_position);
+        }
+
+        positions.reverse();
+        positions
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    pub fn locate_sorted(&self, pattern: &[I]) -> Vec<usize> {
+        let mut positions = self.locate(pattern);
+        positions.sort_unstable();
+        positions
+    }
+
+    /// The substring of the text in the given range.
+    ///
+    /// The r-index does not store samples of the inverse suffix array, so the substring is computed using the
+    /// LF mapping from the end of the text. This needs `n - range.start` LF steps, where `n` is the length of
+    /// the text.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    pub fn extract(&self, range: Range<usize>) -> Vec<I> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Range out of bounds"
+        );
+
+        let rlbwt = &self.run_length_bwt;
+
+        // the sentinel row 0 belongs to the end of the text
+        extract_with_lf(
+            range,
+            rlbwt.len(),
+            0,
+            |row| rlbwt.access(row),
+            |row| rlbwt.lf(row),
+        )
+    }
+
+    // backward search that also keeps track of the text position of the last row of the interval
+    fn interval_rows_with_toehold(&self, pattern: &[I]) -> (usize, usize, usize) {
+        let rlbwt = &self.run_length_bwt;
+        let (mut start_row, mut end_row) = (0, rlbwt.len() + 1);
+        let mut toehold = *self.run_end_samples.last().unwrap();
+
+        for &symbol in pattern.iter().rev() {
+            if start_row == end_row {
+                break;
+            }
+
+            let (next_start_row, next_end_row) = rlbwt.lf_interval(start_row, end_row, symbol);
+
+            if next_start_row < next_end_row {
+                toehold = if rlbwt.access(end_row - 1) == Some(symbol) {
+                    toehold - 1
+                } else {
+                    // the last occurrence of the symbol in the interval is at the end of a run
+                    let run = rlbwt.last_run_of_symbol_before(symbol, end_row).unwrap();
+                    self.run_end_samples[run] - 1
+                };
+            }
+
+            (start_row, end_row) = (next_start_row, next_end_row);
+        }
+
+        (start_row, end_row, toehold)
+    }
+
+    // the text position of the suffix in the row before the row of the suffix at text_position
+    fn phi(&self, text_position: usize) -> usize {
+        let predecessor = self.phi_keys.partition_point(|&key| key <= text_position) - 1;
+        self.phi_values[predecessor] + text_position - self.phi_keys[predecessor]
+    }
+}
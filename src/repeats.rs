@@ -0,0 +1,320 @@
+/*!
+ * Enumerate maximal and supermaximal repeats and find the longest repeated substrings of a text.
+ *
+ * A _repeat_ is a substring that occurs at least twice in the text. All functions of this module take a
+ * [`SuffixArrayWithLcpAndPlcp`] and the text and report every repeat as a [`Repeat`], which carries its length,
+ * its suffix array interval and its occurrences.
+ *
+ * * A repeat is _maximal_ if it can be extended neither to the left nor to the right without losing an
+ *   occurrence, i.e. its occurrences are preceded by at least two different symbols and followed by at least two
+ *   different symbols. The maximal repeats are the LCP intervals whose occurrences are preceded by different
+ *   symbols. [`SuffixArrayWithLcpAndPlcp::maximal_repeats`]
+ * * A repeat is _supermaximal_ if it is maximal and not a substring of another maximal repeat. The supermaximal
+ *   repeats are the LCP intervals whose children are all leaves and whose occurrences are all preceded by
+ *   different symbols. [`SuffixArrayWithLcpAndPlcp::supermaximal_repeats`]
+ * * The _longest repeated substring_ is the longest substring that occurs at least twice, where the occurrences
+ *   may overlap. [`SuffixArrayWithLcpAndPlcp::longest_repeated_substring`]
+ * * The _longest non-overlapping repeated substring_ is the longest substring that occurs at least twice without
+ *   overlap. It is found by computing the leftmost and rightmost occurrence of every LCP interval bottom-up.
+ *   [`SuffixArrayWithLcpAndPlcp::longest_non_overlapping_repeated_substring`]
+ *
+ * The longest repeated substrings only depend on the suffix array and the LCP array, so they don't need the text.
+ * All functions run in linear time in the length of the text, plus the time to sort the reported positions.
+ *
+ * ```
+ * use libsais::SuffixArrayConstruction;
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * // "a" and "abra" are maximal, "bra" is always preceded by "a"
+ * let maximal_repeats = res.maximal_repeats(text, 1);
+ * assert_eq!(maximal_repeats.len(), 2);
+ * assert_eq!(maximal_repeats[0].positions(), &[0, 3, 5, 7, 10]);
+ * assert_eq!(maximal_repeats[1].len(), 4);
+ *
+ * let longest = res.longest_repeated_substring().unwrap();
+ * assert_eq!((longest.len(), longest.positions()), (4, [0, 7].as_slice()));
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The start and the end of the text behave like unique symbols, as if the text was enclosed by sentinels. For
+ * example, in the text `aa`, the repeat `a` is maximal, because its first occurrence is preceded by the start of
+ * the text and its second occurrence is followed by the end of the text. The suffix array of `libsais` does not
+ * contain the suffix that consists only of the sentinel, which is taken into account by these functions.
+ *
+ * For generalized suffix arrays, the LCP values stop at the zero separators (see
+ * [`lcp`](super::lcp#generalized-suffix-array-support)), so repeats never contain a separator. Every separator
+ * before an occurrence is treated as a unique symbol, like the start of the text.
+ */
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement, lcp::SuffixArrayWithLcpAndPlcp, lcp_interval::LcpInterval,
+    search::SuffixArrayInterval, typestate::BufferMode,
+};
+
+/// A substring that occurs at least twice in the text.
+///
+/// See [`repeats`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Repeat {
+    len: usize,
+    interval: SuffixArrayInterval,
+    positions: Vec<usize>,
+}
+
+impl Repeat {
+    /// The length of the repeated substring.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The suffix array interval of the occurrences.
+    pub fn interval(&self) -> SuffixArrayInterval {
+        self.interval
+    }
+
+    /// The number of occurrences, which is at least 2.
+    pub fn num_occurrences(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// The positions of the occurrences, sorted by text position.
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+}
+
+// the symbol before an occurrence, None if it is the start of the text or a separator
+type LeftContext<I> = Option<I>;
+
+impl<'l, 'p, 's, O: OutputElement, SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>
+    SuffixArrayWithLcpAndPlcp<'l, 'p, 's, O, SaB, LcpB, PlcpB>
+{
+    /// All maximal repeats with a length of at least `min_len`, ordered by their suffix array intervals.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the text does not have the same length as the suffix array.
+    pub fn maximal_repeats<I: InputElement>(&self, text: &[I], min_len: usize) -> Vec<Repeat> {
+        self.assert_text_len(text);
+
+        let mut repeats = Vec::new();
+
+        // the left context of a node, or None if its occurrences are preceded by different symbols
+        self.fold_lcp_intervals(
+            |suffix_array_index| Some(self.left_context(text, suffix_array_index)),
+            |lcp_interval, children| {
+                // a unique left context always differs from the others
+                let is_left_maximal = children.iter().any(|&left_context| {
+                    left_context.flatten().is_none() || left_context != children[0]
+                });
+
+                if !is_left_maximal {
+                    return children[0];
+                }
+
+                if lcp_interval.lcp() >= min_len.max(1) {
+                    repeats.push(self.repeat(lcp_interval.lcp(), lcp_interval.interval()));
+                }
+
+                None
+            },
+        );
+
+        sort_repeats(&mut repeats);
+
+        repeats
+    }
+
+    /// All supermaximal repeats with a length of at least `min_len`, ordered by their suffix array intervals.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the text does not have the same length as the suffix array.
+    pub fn supermaximal_repeats<I: InputElement>(&self, text: &[I], min_len: usize) -> Vec<Repeat> {
+        self.assert_text_len(text);
+
+        let mut repeats: Vec<_> = self
+            .lcp_intervals()
+            .filter(|lcp_interval| {
+                lcp_interval.lcp() >= min_len.max(1)
+                    && self.has_distinct_leaf_left_contexts(text, lcp_interval)
+            })
+            .map(|lcp_interval| self.repeat(lcp_interval.lcp(), lcp_interval.interval()))
+            .collect();
+
+        sort_repeats(&mut repeats);
+
+        repeats
+    }
+
+    /// The longest substring that occurs at least twice, where the occurrences may overlap. If there are several,
+    /// the lexicographically smallest one is returned. Returns `None` if no symbol occurs twice.
+    pub fn longest_repeated_substring(&self) -> Option<Repeat> {
+        let lcp = self.lcp();
+        let (suffix_array_index, len) = (1..lcp.len())
+            .map(|i| (i, <usize as NumCast>::from(lcp[i]).unwrap()))
+            .rev()
+            .max_by_key(|&(_, len)| len)?;
+
+        (len > 0).then(|| self.repeat(len, self.enclosing_interval(suffix_array_index, len)))
+    }
+
+    /// The longest substring that occurs at least twice without overlap. If there are several, the one with the
+    /// leftmost occurrence is returned. Returns `None` if no symbol occurs twice.
+    ///
+    /// The positions of the returned repeat are all of its occurrences, which may overlap each other. Its
+    /// leftmost and rightmost occurrence never overlap.
+    pub fn longest_non_overlapping_repeated_substring(&self) -> Option<Repeat> {
+        // the length, the leftmost occurrence and a suffix array index of an occurrence of the
+        // best repeat
+        let mut best: Option<(usize, usize, usize)> = None;
+
+        self.fold_lcp_intervals(
+            |suffix_array_index| {
+                let suffix = self.suffix(suffix_array_index);
+                (suffix, suffix)
+            },
+            |lcp_interval, children| {
+                let leftmost = children
+                    .iter()
+                    .map(|&(leftmost, _)| leftmost)
+                    .min()
+                    .unwrap();
+                let rightmost = children
+                    .iter()
+                    .map(|&(_, rightmost)| rightmost)
+                    .max()
+                    .unwrap();
+                let len = lcp_interval.lcp().min(rightmost - leftmost);
+
+                let is_better = best.is_none_or(|(best_len, best_leftmost, _)| {
+                    len > best_len || (len == best_len && leftmost < best_leftmost)
+                });
+
+                if len > 0 && is_better {
+                    best = Some((len, leftmost, lcp_interval.interval().start()));
+                }
+
+                (leftmost, rightmost)
+            },
+        );
+
+        best.map(|(len, _, suffix_array_index)| {
+            self.repeat(len, self.enclosing_interval(suffix_array_index, len))
+        })
+    }
+
+    fn assert_text_len<I: InputElement>(&self, text: &[I]) {
+        assert_eq!(
+            text.len(),
+            self.suffix_array().len(),
+            "The text must have the same length as the suffix array"
+        );
+    }
+
+    fn suffix(&self, suffix_array_index: usize) -> usize {
+        <usize as NumCast>::from(self.suffix_array()[suffix_array_index]).unwrap()
+    }
+
+    fn left_context<I: InputElement>(
+        &self,
+        text: &[I],
+        suffix_array_index: usize,
+    ) -> LeftContext<I> {
+        let preceding_symbol = text[self.suffix(suffix_array_index).checked_sub(1)?];
+
+        if self.is_generalized_suffix_array() && preceding_symbol == I::zero() {
+            None
+        } else {
+            Some(preceding_symbol)
+        }
+    }
+
+    fn has_distinct_leaf_left_contexts<I: InputElement>(
+        &self,
+        text: &[I],
+        lcp_interval: &LcpInterval,
+    ) -> bool {
+        if lcp_interval
+            .child_intervals()
+            .iter()
+            .any(|child_interval| child_interval.len() > 1)
+        {
+            return false;
+        }
+
+        let mut left_contexts: Vec<_> = lcp_interval
+            .interval()
+            .range()
+            .filter_map(|suffix_array_index| self.left_context(text, suffix_array_index))
+            .collect();
+        let num_symbols = left_contexts.len();
+
+        left_contexts.sort_unstable();
+        left_contexts.dedup();
+
+        left_contexts.len() == num_symbols
+    }
+
+    // the interval of the prefix of the given length of the suffix at the given suffix array index
+    fn enclosing_interval(&self, suffix_array_index: usize, len: usize) -> SuffixArrayInterval {
+        let lcp = self.lcp();
+        let is_in_interval = |i: usize| <usize as NumCast>::from(lcp[i]).unwrap() >= len;
+
+        let mut start = suffix_array_index;
+        while start > 0 && is_in_interval(start) {
+            start -= 1;
+        }
+
+        let mut end = suffix_array_index + 1;
+        while end < lcp.len() && is_in_interval(end) {
+            end += 1;
+        }
+
+        SuffixArrayInterval::new(start, end)
+    }
+
+    fn repeat(&self, len: usize, interval: SuffixArrayInterval) -> Repeat {
+        let mut positions: Vec<_> = interval
+            .range()
+            .map(|suffix_array_index| self.suffix(suffix_array_index))
+            .collect();
+        positions.sort_unstable();
+
+        Repeat {
+            len,
+            interval,
+            positions,
+        }
+    }
+}
+
+fn sort_repeats(repeats: &mut [Repeat]) {
+    repeats.sort_unstable_by_key(|repeat| repeat.interval);
+}
@@ -0,0 +1,656 @@
+/*!
+ * Search for patterns in a text using its suffix array.
+ *
+ * All suffixes of the text that start with a given pattern are stored consecutively in the suffix array.
+ * Therefore, the occurrences of a pattern are described by a [`SuffixArrayInterval`], which can be found by
+ * binary search in `O(m log n)` character comparisons, where `m` is the length of the pattern and `n` is the
+ * length of the text.
+ *
+ * The search API is available on [`SuffixArrayWithText`] and on
+ * [`SuffixArrayWithLcpAndPlcp`]. Since the latter type does not keep a reference to the text, the text has to be
+ * passed to its search functions. It must be the text for which the suffix array was constructed.
+ *
+ * ```
+ * use libsais::SuffixArrayConstruction;
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * assert_eq!(res.count(b"abra"), 2);
+ * assert_eq!(res.locate_sorted(b"abra"), vec![0, 7]);
+ *
+ * // the interval can also be refined one symbol at a time
+ * let interval = res.interval(b"a");
+ * let interval = res.refine_interval(interval, 1, b'b');
+ * assert_eq!(interval, res.interval(b"ab"));
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The suffix array follows the conventions of `libsais` (see [`suffix_array`](super::suffix_array)), in which the
+ * text behaves as if it were terminated by a lexicographically smallest sentinel. When searching, the end of the
+ * text therefore compares smaller than every symbol. Zero symbols in the pattern are compared like any other symbol,
+ * which means that for a generalized suffix array, a pattern containing a zero symbol can match the end of a text
+ * in the set.
+ *
+ * # LCP-Accelerated Search
+ *
+ * When the LCP array is available, an [`LcpLrIndex`] can be created from a [`SuffixArrayWithLcpAndPlcp`]. It
+ * precomputes the LCP-LR arrays of [Manber and Myers], which store the longest common prefixes of the suffixes
+ * compared during binary search. Using these values, character comparisons are never repeated and a pattern
+ * can be found in `O(m + log n)` time. The LCP-LR arrays need two additional arrays of the output element type.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, search::LcpLrIndex};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let index = LcpLrIndex::new(&res, text);
+ *
+ * assert_eq!(index.count(b"abra"), 2);
+ * assert_eq!(index.interval(b"abra"), res.interval(text, b"abra"));
+ * ```
+ *
+ * # Batched Search
+ *
+ * When many patterns are searched in the same suffix array, [`SuffixArrayWithText::batch_intervals`] sorts the
+ * patterns lexicographically and processes them in this order. The intervals of the prefixes of the previous
+ * pattern are kept, so the interval narrowing for a common prefix of consecutive patterns is only done once. The
+ * sorted patterns are split into one batch per thread and the batches are searched in parallel using
+ * [`std::thread::scope`]. This does not require the `openmp` feature.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, ThreadCount};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let patterns = [b"abra".as_slice(), b"cad", b"abr", b"x"];
+ * let intervals = res.batch_intervals(&patterns, ThreadCount::fixed(2));
+ *
+ * assert_eq!(intervals[0], res.interval(b"abra"));
+ * assert_eq!(intervals[1].len(), 1);
+ * assert!(intervals[3].is_empty());
+ * ```
+ *
+ * [Manber and Myers]: https://doi.org/10.1137/0222058
+ */
+
+use std::ops::Range;
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement, ThreadCount, lcp::SuffixArrayWithLcpAndPlcp,
+    suffix_array::SuffixArrayWithText, typestate::BufferMode,
+};
+
+/// A half-open interval `[start, end)` of the suffix array.
+///
+/// Usually, it contains the suffixes that start with a certain pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SuffixArrayInterval {
+    start: usize,
+    end: usize,
+}
+
+impl SuffixArrayInterval {
+    /// # Panics
+    ///
+    /// If `start > end`.
+    pub fn new(start: usize, end: usize) -> Self {
+        assert!(start <= end, "Start of an interval cannot be after its end");
+
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// The number of suffixes in the interval, which is the number of occurrences of a pattern.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl From<SuffixArrayInterval> for Range<usize> {
+    fn from(interval: SuffixArrayInterval) -> Self {
+        interval.range()
+    }
+}
+
+/// An iterator over the text positions of the occurrences of a pattern, in suffix array order.
+#[derive(Debug, Clone)]
+pub struct Occurrences<'a, O: OutputElement> {
+    suffix_array_entries: std::slice::Iter<'a, O>,
+}
+
+impl<'a, O: OutputElement> Occurrences<'a, O> {
+    pub(crate) fn new(suffix_array_entries: &'a [O]) -> Self {
+        Self {
+            suffix_array_entries: suffix_array_entries.iter(),
+        }
+    }
+
+    /// Collect the remaining occurrences, sorted by their position in the text.
+    pub fn into_sorted_vec(self) -> Vec<usize> {
+        let mut positions: Vec<_> = self.collect();
+        positions.sort_unstable();
+        positions
+    }
+}
+
+impl<'a, O: OutputElement> Iterator for Occurrences<'a, O> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.suffix_array_entries
+            .next()
+            .map(|&entry| <usize as NumCast>::from(entry).unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.suffix_array_entries.size_hint()
+    }
+}
+
+impl<'a, O: OutputElement> DoubleEndedIterator for Occurrences<'a, O> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.suffix_array_entries
+            .next_back()
+            .map(|&entry| <usize as NumCast>::from(entry).unwrap())
+    }
+}
+
+impl<'a, O: OutputElement> ExactSizeIterator for Occurrences<'a, O> {}
+
+impl<'s, 't, I: InputElement, O: OutputElement, B: BufferMode>
+    SuffixArrayWithText<'s, 't, I, O, B>
+{
+    /// The suffix array interval of all suffixes that start with `pattern`.
+    ///
+    /// For the empty pattern, this is the whole suffix array.
+    pub fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        interval(self.text, &self.suffix_array.buffer, pattern)
+    }
+
+    /// Narrow down an interval when the pattern is extended by one symbol.
+    ///
+    /// `interval` must be the interval of a pattern of length `pattern_len`. The returned interval is the
+    /// interval of the pattern extended by `symbol`.
+    pub fn refine_interval(
+        &self,
+        interval: SuffixArrayInterval,
+        pattern_len: usize,
+        symbol: I,
+    ) -> SuffixArrayInterval {
+        refine_interval(
+            self.text,
+            &self.suffix_array.buffer,
+            interval,
+            pattern_len,
+            symbol,
+        )
+    }
+
+    /// The number of occurrences of `pattern` in the text.
+    pub fn count(&self, pattern: &[I]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    pub fn locate(&self, pattern: &[I]) -> Occurrences<'_, O> {
+        Occurrences::new(&self.suffix_array.buffer[self.interval(pattern).range()])
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    pub fn locate_sorted(&self, pattern: &[I]) -> Vec<usize> {
+        self.locate(pattern).into_sorted_vec()
+    }
+
+    /// The suffix array intervals of all `patterns`, in the order of the patterns.
+    ///
+    /// The work for common prefixes of the patterns is shared and the patterns are searched in parallel using
+    /// `thread_count` threads. [`ThreadCount::openmp_default`] uses the available parallelism of the system.
+    /// See [`search`](self#batched-search) for details.
+    pub fn batch_intervals<P: AsRef<[I]> + Sync>(
+        &self,
+        patterns: &[P],
+        thread_count: ThreadCount,
+    ) -> Vec<SuffixArrayInterval> {
+        batch_intervals(self.text, &self.suffix_array.buffer, patterns, thread_count)
+    }
+}
+
+impl<'l, 'p, 's, O: OutputElement, SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>
+    SuffixArrayWithLcpAndPlcp<'l, 'p, 's, O, SaB, LcpB, PlcpB>
+{
+    /// The suffix array interval of all suffixes of `text` that start with `pattern`.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    /// See [`SuffixArrayWithText::interval`] for details.
+    pub fn interval<I: InputElement>(&self, text: &[I], pattern: &[I]) -> SuffixArrayInterval {
+        interval(text, &self.suffix_array.buffer, pattern)
+    }
+
+    /// See [`SuffixArrayWithText::refine_interval`] for details.
+    pub fn refine_interval<I: InputElement>(
+        &self,
+        text: &[I],
+        interval: SuffixArrayInterval,
+        pattern_len: usize,
+        symbol: I,
+    ) -> SuffixArrayInterval {
+        refine_interval(
+            text,
+            &self.suffix_array.buffer,
+            interval,
+            pattern_len,
+            symbol,
+        )
+    }
+
+    /// See [`SuffixArrayWithText::count`] for details.
+    pub fn count<I: InputElement>(&self, text: &[I], pattern: &[I]) -> usize {
+        self.interval(text, pattern).len()
+    }
+
+    /// See [`SuffixArrayWithText::locate`] for details.
+    pub fn locate<I: InputElement>(&self, text: &[I], pattern: &[I]) -> Occurrences<'_, O> {
+        Occurrences::new(&self.suffix_array.buffer[self.interval(text, pattern).range()])
+    }
+
+    /// See [`SuffixArrayWithText::locate_sorted`] for details.
+    pub fn locate_sorted<I: InputElement>(&self, text: &[I], pattern: &[I]) -> Vec<usize> {
+        self.locate(text, pattern).into_sorted_vec()
+    }
+
+    /// See [`SuffixArrayWithText::batch_intervals`] for details.
+    pub fn batch_intervals<I: InputElement, P: AsRef<[I]> + Sync>(
+        &self,
+        text: &[I],
+        patterns: &[P],
+        thread_count: ThreadCount,
+    ) -> Vec<SuffixArrayInterval> {
+        batch_intervals(text, &self.suffix_array.buffer, patterns, thread_count)
+    }
+}
+
+/// A search index that uses the LCP-LR arrays of [Manber and Myers] to find patterns in `O(m + log n)` time.
+///
+/// See [`search`](self#lcp-accelerated-search) for details.
+///
+/// [Manber and Myers]: https://doi.org/10.1137/0222058
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LcpLrIndex<'a, I: InputElement, O: OutputElement> {
+    text: &'a [I],
+    suffix_array: &'a [O],
+    // for every possible midpoint of the binary search, the longest common prefix with the left
+    // and right boundary of the search window
+    left_lcp: Vec<O>,
+    right_lcp: Vec<O>,
+}
+
+impl<'a, I: InputElement, O: OutputElement> LcpLrIndex<'a, I, O> {
+    /// Precompute the LCP-LR arrays from the LCP array of `index`.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the text does not have the same length as the suffix array.
+    pub fn new<SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>(
+        index: &'a SuffixArrayWithLcpAndPlcp<'_, '_, '_, O, SaB, LcpB, PlcpB>,
+        text: &'a [I],
+    ) -> Self {
+        assert_eq!(
+            text.len(),
+            index.suffix_array().len(),
+            "The text must have the same length as the suffix array"
+        );
+
+        let len = text.len();
+        let mut lcp_lr_index = Self {
+            text,
+            suffix_array: index.suffix_array(),
+            left_lcp: vec![O::zero(); len],
+            right_lcp: vec![O::zero(); len],
+        };
+
+        if len > 0 {
+            lcp_lr_index.fill_lcp_lr(index.lcp(), 0, len + 1);
+        }
+
+        lcp_lr_index
+    }
+
+    pub fn text(&self) -> &'a [I] {
+        self.text
+    }
+
+    pub fn suffix_array(&self) -> &'a [O] {
+        self.suffix_array
+    }
+
+    /// The suffix array interval of all suffixes that start with `pattern`.
+    pub fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        SuffixArrayInterval::new(
+            self.partition_point(pattern, false),
+            self.partition_point(pattern, true),
+        )
+    }
+
+    /// The number of occurrences of `pattern` in the text.
+    pub fn count(&self, pattern: &[I]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    pub fn locate(&self, pattern: &[I]) -> Occurrences<'a, O> {
+        Occurrences::new(&self.suffix_array[self.interval(pattern).range()])
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    pub fn locate_sorted(&self, pattern: &[I]) -> Vec<usize> {
+        self.locate(pattern).into_sorted_vec()
+    }
+
+    // The binary search window is described by the exclusive boundaries left and right, which are
+    // shifted by one compared to the suffix array indices. 0 and len + 1 are virtual boundaries that
+    // have no common prefix with any other suffix. Returns the LCP of the left and right boundary.
+    fn fill_lcp_lr(&mut self, lcp: &[O], left: usize, right: usize) -> O {
+        let is_virtual = left == 0 || right == self.suffix_array.len() + 1;
+
+        if right - left == 1 {
+            return if is_virtual { O::zero() } else { lcp[left] };
+        }
+
+        let middle = (left + right) / 2;
+        let left_lcp = self.fill_lcp_lr(lcp, left, middle);
+        let right_lcp = self.fill_lcp_lr(lcp, middle, right);
+
+        self.left_lcp[middle - 1] = left_lcp;
+        self.right_lcp[middle - 1] = right_lcp;
+
+        if is_virtual {
+            O::zero()
+        } else {
+            left_lcp.min(right_lcp)
+        }
+    }
+
+    // Returns the first suffix array index of a suffix that is greater than the pattern. If `include_equal`
+    // is false, suffixes that start with the pattern are also considered greater.
+    fn partition_point(&self, pattern: &[I], include_equal: bool) -> usize {
+        let pattern_len = pattern.len();
+        let (mut left, mut right) = (0, self.suffix_array.len() + 1);
+        let (mut left_match_len, mut right_match_len) = (0, 0);
+
+        while right - left > 1 {
+            let middle = (left + right) / 2;
+
+            // the suffix at middle is compared to the pattern starting from this offset
+            let offset = if left_match_len >= right_match_len {
+                let lcp_with_left = self.lcp_lr_value(&self.left_lcp, middle, pattern_len);
+
+                if lcp_with_left > left_match_len {
+                    left = middle;
+                    continue;
+                } else if lcp_with_left < left_match_len {
+                    right = middle;
+                    right_match_len = lcp_with_left;
+                    continue;
+                }
+
+                left_match_len
+            } else {
+                let lcp_with_right = self.lcp_lr_value(&self.right_lcp, middle, pattern_len);
+
+                if lcp_with_right > right_match_len {
+                    right = middle;
+                    continue;
+                } else if lcp_with_right < right_match_len {
+                    left = middle;
+                    left_match_len = lcp_with_right;
+                    continue;
+                }
+
+                right_match_len
+            };
+
+            let suffix = self.suffix_array[middle - 1];
+            let match_len = offset
+                + pattern[offset..]
+                    .iter()
+                    .zip(
+                        (offset..)
+                            .map_while(|suffix_offset| symbol_at(self.text, suffix, suffix_offset)),
+                    )
+                    .take_while(|&(&pattern_symbol, suffix_symbol)| pattern_symbol == suffix_symbol)
+                    .count();
+
+            let goes_left = if match_len == pattern_len {
+                include_equal
+            } else {
+                symbol_at(self.text, suffix, match_len)
+                    .is_none_or(|suffix_symbol| suffix_symbol < pattern[match_len])
+            };
+
+            if goes_left {
+                left = middle;
+                left_match_len = match_len;
+            } else {
+                right = middle;
+                right_match_len = match_len;
+            }
+        }
+
+        right - 1
+    }
+
+    fn lcp_lr_value(&self, lcp_lr: &[O], middle: usize, pattern_len: usize) -> usize {
+        <usize as NumCast>::from(lcp_lr[middle - 1])
+            .unwrap()
+            .min(pattern_len)
+    }
+}
+
+pub(crate) fn interval<I: InputElement, O: OutputElement>(
+    text: &[I],
+    suffix_array: &[O],
+    pattern: &[I],
+) -> SuffixArrayInterval {
+    let mut interval = SuffixArrayInterval::new(0, suffix_array.len());
+
+    for (pattern_len, &symbol) in pattern.iter().enumerate() {
+        if interval.is_empty() {
+            break;
+        }
+
+        interval = refine_interval(text, suffix_array, interval, pattern_len, symbol);
+    }
+
+    interval
+}
+
+fn batch_intervals<I: InputElement, O: OutputElement, P: AsRef<[I]> + Sync>(
+    text: &[I],
+    suffix_array: &[O],
+    patterns: &[P],
+    thread_count: ThreadCount,
+) -> Vec<SuffixArrayInterval> {
+    let mut order: Vec<_> = (0..patterns.len()).collect();
+    order.sort_unstable_by_key(|&i| patterns[i].as_ref());
+
+    let num_threads = match thread_count.value {
+        0 => std::thread::available_parallelism().map_or(1, |num_threads| num_threads.get()),
+        value => value as usize,
+    };
+    let batch_size = order.len().div_ceil(num_threads).max(1);
+
+    let mut intervals = vec![SuffixArrayInterval::new(0, 0); patterns.len()];
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = order
+            .chunks(batch_size)
+            .map(|batch| {
+                scope.spawn(move || sorted_batch_intervals(text, suffix_array, patterns, batch))
+            })
+            .collect();
+
+        for (handle, batch) in handles.into_iter().zip(order.chunks(batch_size)) {
+            let batch_intervals = handle.join().expect("Search thread should not panic");
+
+            for (&pattern_index, interval) in batch.iter().zip(batch_intervals) {
+                intervals[pattern_index] = interval;
+            }
+        }
+    });
+
+    intervals
+}
+
+// the intervals of the patterns at the given indices, which must be sorted by pattern
+fn sorted_batch_intervals<I: InputElement, O: OutputElement, P: AsRef<[I]>>(
+    text: &[I],
+    suffix_array: &[O],
+    patterns: &[P],
+    batch: &[usize],
+) -> Vec<SuffixArrayInterval> {
+    // prefix_intervals[i] is the interval of the prefix of length i of the previous pattern
+    let mut prefix_intervals = vec![SuffixArrayInterval::new(0, suffix_array.len())];
+    let mut previous_pattern: &[I] = &[];
+
+    batch
+        .iter()
+        .map(|&pattern_index| {
+            let pattern = patterns[pattern_index].as_ref();
+
+            let common_prefix_len = pattern
+                .iter()
+                .zip(previous_pattern)
+                .take_while(|(a, b)| a == b)
+                .count();
+            prefix_intervals.truncate(common_prefix_len + 1);
+
+            for (pattern_len, &symbol) in pattern.iter().enumerate().skip(common_prefix_len) {
+                let interval = *prefix_intervals.last().unwrap();
+
+                if interval.is_empty() {
+                    break;
+                }
+
+                prefix_intervals.push(refine_interval(
+                    text,
+                    suffix_array,
+                    interval,
+                    pattern_len,
+                    symbol,
+                ));
+            }
+
+            previous_pattern = pattern;
+
+            *prefix_intervals.last().unwrap()
+        })
+        .collect()
+}
+
+pub(crate) fn refine_interval<I: InputElement, O: OutputElement>(
+    text: &[I],
+    suffix_array: &[O],
+    interval: SuffixArrayInterval,
+    pattern_len: usize,
+    symbol: I,
+) -> SuffixArrayInterval {
+    let suffixes = &suffix_array[interval.range()];
+
+    // the end of the text (virtual sentinel) is represented by None, which is smaller than all symbols
+    let start = suffixes.partition_point(|&suffix| {
+        symbol_at(text, suffix, pattern_len).is_none_or(|suffix_symbol| suffix_symbol < symbol)
+    });
+    let end = start
+        + suffixes[start..]
+            .partition_point(|&suffix| symbol_at(text, suffix, pattern_len) == Some(symbol));
+
+    SuffixArrayInterval::new(interval.start + start, interval.start + end)
+}
+
+// the non-empty intervals of all extensions by one symbol of a pattern of length pattern_len with
+// the given interval, together with the symbols, in ascending order
+pub(crate) fn child_intervals<I: InputElement, O: OutputElement>(
+    text: &[I],
+    suffix_array: &[O],
+    interval: SuffixArrayInterval,
+    pattern_len: usize,
+) -> Vec<(I, SuffixArrayInterval)> {
+    let mut children = Vec::new();
+    let mut start = interval.start;
+
+    while start < interval.end {
+        match symbol_at(text, suffix_array[start], pattern_len) {
+            // suffixes that end after pattern_len symbols come first in the interval
+            None => start += 1,
+            Some(symbol) => {
+                let child = refine_interval(
+                    text,
+                    suffix_array,
+                    SuffixArrayInterval::new(start, interval.end),
+                    pattern_len,
+                    symbol,
+                );
+                start = child.end;
+                children.push((symbol, child));
+            }
+        }
+    }
+
+    children
+}
+
+// the symbol at the given offset of the suffix, or None if the suffix is too short
+pub(crate) fn symbol_at<I: InputElement, O: OutputElement>(
+    text: &[I],
+    suffix: O,
+    offset: usize,
+) -> Option<I> {
+    text.get(<usize as NumCast>::from(suffix).unwrap() + offset)
+        .copied()
+}
@@ -0,0 +1,287 @@
+/*!
+ * Find super-maximal exact matches (SMEMs) and maximal exact matches (MEMs) of a query using a
+ * [`BidirectionalFmIndex`].
+ *
+ * A substring `Q[i..j)` of the query is a _super-maximal exact match_ ([`Smem`]) if it occurs in the text, but
+ * neither `Q[i - 1..j)` nor `Q[i..j + 1)` does. In other words, it is an exact match that is not contained in any
+ * other exact match of the query. SMEMs are computed with the [algorithm of Li], which extends matches to the
+ * right and then to the left, in `O(m)` extensions per SMEM, where `m` is the length of the query. Every SMEM is
+ * reported with its [`BidirectionalInterval`], so the occurrences can be located later using
+ * [`BidirectionalFmIndex::locate_interval`].
+ *
+ * A _maximal exact match_ ([`Mem`]) is a pair of a query position and a text position at which an exact match
+ * starts that can be extended neither to the left nor to the right, because the preceding or following symbols
+ * differ or one of the sequences ends. Every SMEM occurrence is a MEM, but there are usually many more MEMs.
+ * They are found by extending a match from every query position to the right and checking the occurrences that
+ * are lost in every step for left maximality.
+ *
+ * ```
+ * use libsais::bidirectional_fm_index::BidirectionalFmIndex;
+ *
+ * let text = b"ACGTACGGACGTTT".as_slice();
+ * let index = BidirectionalFmIndex::new(text, 4).unwrap();
+ *
+ * let smems = index.smems(b"TACGTTA", 1);
+ *
+ * assert_eq!(smems.len(), 3);
+ * assert_eq!(smems[0].query_range(), 0..4);
+ * assert_eq!(smems[1].query_range(), 1..6);
+ * assert_eq!(smems[2].query_range(), 5..7);
+ * assert_eq!(index.locate_interval(smems[1].interval()), vec![8]);
+ *
+ * let mems = index.mems(b"TACGTTA", 4);
+ *
+ * assert_eq!(mems.len(), 3);
+ * assert_eq!((mems[0].query_start(), mems[0].text_start(), mems[0].len()), (0, 3, 4));
+ * ```
+ *
+ * [algorithm of Li]: https://doi.org/10.1093/bioinformatics/bts280
+ */
+
+use std::ops::Range;
+
+use crate::{
+    SmallAlphabet,
+    bidirectional_fm_index::{BidirectionalFmIndex, BidirectionalInterval},
+};
+
+/// A super-maximal exact match of a query.
+///
+/// See [`smem`](self) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Smem {
+    query_start: usize,
+    query_end: usize,
+    interval: BidirectionalInterval,
+}
+
+impl Smem {
+    /// The range of the query that is matched.
+    pub fn query_range(&self) -> Range<usize> {
+        self.query_start..self.query_end
+    }
+
+    /// The length of the match.
+    pub fn len(&self) -> usize {
+        self.query_end - self.query_start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.query_start == self.query_end
+    }
+
+    /// The interval of the matched substring in the bidirectional FM-index.
+    pub fn interval(&self) -> BidirectionalInterval {
+        self.interval
+    }
+}
+
+/// A maximal exact match between a query position and a text position.
+///
+/// See [`smem`](self) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mem {
+    query_start: usize,
+    text_start: usize,
+    len: usize,
+}
+
+impl Mem {
+    pub fn query_start(&self) -> usize {
+        self.query_start
+    }
+
+    pub fn text_start(&self) -> usize {
+        self.text_start
+    }
+
+    /// The length of the match.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<I: SmallAlphabet> BidirectionalFmIndex<I> {
+    /// All SMEMs of `query` with a length of at least `min_len`, sorted by their position in the query.
+    pub fn smems(&self, query: &[I], min_len: usize) -> Vec<Smem> {
+        let mut smems = Vec::new();
+        let mut query_position = 0;
+
+        while query_position < query.len() {
+            query_position = self.smems_containing(query, query_position, &mut smems);
+        }
+
+        smems.retain(|smem| smem.len() >= min_len.max(1));
+        smems.sort_unstable_by_key(|smem| smem.query_start);
+
+        smems
+    }
+
+    /// All MEMs of `query` with a length of at least `min_len`, sorted by query position and text position.
+    ///
+    /// The text positions are computed using the suffix array sample of the forward index.
+    ///
+    /// For every query position, the match is extended to the right and the occurrences that are lost in a step
+    /// are checked for left maximality. Runs of lost occurrences that are all preceded by the previous query
+    /// symbol are skipped with a single rank query, but the others are visited one by one. In the worst case, this
+    /// takes `Θ(m · occ)` time for a query of length `m`, where `occ` is the largest number of occurrences of a
+    /// query substring of length `min_len`. This is `O(m · n)` for a text of length `n`. The time to locate the
+    /// reported MEMs comes on top.
+    pub fn mems(&self, query: &[I], min_len: usize) -> Vec<Mem> {
+        let forward_index = self.forward_index();
+        let mut mems = Vec::new();
+
+        for query_start in 0..query.len() {
+            let preceding_symbol = query_start.checked_sub(1).map(|i| query[i]);
+            let mut interval = self.root_interval();
+            let mut query_end = query_start;
+
+            loop {
+                let extended = query
+                    .get(query_end)
+                    .map(|&symbol| self.extend_right(interval, symbol))
+                    .filter(|extended| !extended.is_empty());
+
+                if query_end - query_start >= min_len.max(1) {
+                    // the occurrences of the extended match form a subinterval, all other
+                    // occurrences are right maximal
+                    let forward = interval.forward();
+                    let (extended_start, extended_end) = extended
+                        .map_or((forward.end(), forward.end()), |extended| {
+                            (extended.forward().start(), extended.forward().end())
+                        });
+
+                    // rows that are all preceded by preceding_symbol are not left maximal
+                    let has_left_maximal_rows = |start: usize, end: usize| {
+                        preceding_symbol.is_none_or(|symbol| {
+                            let (lf_start, lf_end) =
+                                forward_index.lf_interval(start + 1, end + 1, symbol);
+                            lf_end - lf_start < end - start
+                        })
+                    };
+                    let lost_ranges =
+                        [forward.start()..extended_start, extended_end..forward.end()];
+
+                    for suffix_array_index in lost_ranges
+                        .into_iter()
+                        .filter(|range| has_left_maximal_rows(range.start, range.end))
+                        .flatten()
+                    {
+                        // row suffix_array_index + 1 of the BWT matrix belongs to this suffix
+                        let is_left_maximal = preceding_symbol.is_none()
+                            || forward_index.last_column_symbol(suffix_array_index + 1)
+                                != preceding_symbol;
+
+                        if is_left_maximal {
+                            mems.push(Mem {
+                                query_start,
+                                text_start: forward_index.suffix_array_entry(suffix_array_index),
+                                len: query_end - query_start,
+                            });
+                        }
+                    }
+                }
+
+                match extended {
+                    Some(extended) => {
+                        interval = extended;
+                        query_end += 1;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        mems.sort_unstable();
+
+        mems
+    }
+
+    // Finds all SMEMs that contain the given query position and returns the end of the longest
+    // match that starts at this position, which is the position for the next call.
+    fn smems_containing(&self, query: &[I], query_position: usize, smems: &mut Vec<Smem>) -> usize {
+        let mut interval = self.extend_right(self.root_interval(), query[query_position]);
+
+        if interval.is_empty() {
+            return query_position + 1;
+        }
+
+        // the matches starting at query_position with their end, whenever the number of
+        // occurrences changes
+        let mut matches = Vec::new();
+        let mut query_end = query_position + 1;
+
+        loop {
+            if query_end == query.len() {
+                matches.push((interval, query_end));
+                break;
+            }
+
+            let extended = self.extend_right(interval, query[query_end]);
+
+            if extended.len() != interval.len() {
+                matches.push((interval, query_end));
+            }
+
+            if extended.is_empty() {
+                break;
+            }
+
+            interval = extended;
+            query_end += 1;
+        }
+
+        // longest match first
+        matches.reverse();
+        let next_query_position = matches[0].1;
+        let num_previous_smems = smems.len();
+
+        for query_start in (0..=query_position).rev() {
+            let mut extended_matches: Vec<(BidirectionalInterval, usize)> = Vec::new();
+
+            for &(interval, query_end) in &matches {
+                let extended = query_start
+                    .checked_sub(1)
+                    .map(|i| self.extend_left(interval, query[i]))
+                    .filter(|extended| !extended.is_empty());
+
+                match extended {
+                    // if a longer match could be extended, this match is contained in it
+                    None if extended_matches.is_empty() => {
+                        let is_contained = smems.len() > num_previous_smems
+                            && smems.last().unwrap().query_start <= query_start;
+
+                        if !is_contained {
+                            smems.push(Smem {
+                                query_start,
+                                query_end,
+                                interval,
+                            });
+                        }
+                    }
+                    // only keep the longest of the matches with the same occurrences
+                    Some(extended)
+                        if extended_matches
+                            .last()
+                            .is_none_or(|(last, _)| last.len() != extended.len()) =>
+                    {
+                        extended_matches.push((extended, query_end));
+                    }
+                    _ => {}
+                }
+            }
+
+            if extended_matches.is_empty() {
+                break;
+            }
+
+            matches = extended_matches;
+        }
+
+        next_query_position
+    }
+}
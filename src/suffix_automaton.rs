@@ -0,0 +1,467 @@
+/*!
+ * Build a suffix automaton (directed acyclic word graph, DAWG) from a suffix array and its LCP array.
+ *
+ * The [`SuffixAutomaton`] is the smallest deterministic automaton that accepts exactly the substrings of a text.
+ * It is built in linear time from the virtual suffix tree that is given by the suffix array and the LCP array:
+ *
+ * * The states are the nodes of the virtual suffix tree. A state represents all substrings of the text that
+ *   occur at the same set of text positions, i.e. that have the same suffix array interval.
+ * * The suffix links are the parent pointers of the virtual suffix tree.
+ * * The transitions are the Weiner links of the virtual suffix tree. The transition of a state with a symbol `c`
+ *   leads to the state of the substrings that are extended to the left by `c`.
+ *
+ * Therefore, the automaton is the suffix automaton of the _reversed_ text and reads patterns from right to left,
+ * like the backward search of the [`FmIndex`](super::fm_index::FmIndex). The functions of this module take the
+ * patterns in their usual order and handle the direction internally. The automaton has at most `2n` states and
+ * `3n` transitions and does not need the text or suffix array after its construction. The [`matching_statistics`]
+ * of a query are computed with the same scan as [`SuffixAutomaton::longest_common_factor`].
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, suffix_automaton::SuffixAutomaton};
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .plcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap()
+ *     .lcp_construction()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let automaton = SuffixAutomaton::new(&res, text);
+ *
+ * assert!(automaton.contains(b"cadab"));
+ * assert!(!automaton.contains(b"abrab"));
+ * assert_eq!(automaton.longest_common_factor(b"xxcadabrxx"), 2..8);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * The states of the automaton also store their suffix array intervals in the convention of `libsais`, such that
+ * the occurrences of a pattern can be located using the suffix array. Generalized suffix arrays are not supported,
+ * because their LCP values stop at the separators (see [`lcp`](super::lcp#generalized-suffix-array-support)).
+ *
+ * [`matching_statistics`]: super::matching_statistics
+ */
+
+use std::{collections::HashMap, ops::Range};
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement, lcp::SuffixArrayWithLcpAndPlcp, lcp_interval::LcpIntervals,
+    search::SuffixArrayInterval, typestate::BufferMode,
+};
+
+// the suffix link of the initial state
+const NO_SUFFIX_LINK: usize = usize::MAX;
+
+// a symbol that precedes occurrences of a substring, with the smallest and largest suffix array
+// index of the occurrences of the extended substring
+type PrecedingSymbol<I> = (I, usize, usize);
+
+/// The suffix automaton of a text, built from its suffix array and LCP array.
+///
+/// See [`suffix_automaton`](self) for details. States are identified by indices in `0..num_states()`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SuffixAutomaton<I: InputElement> {
+    intervals: Vec<SuffixArrayInterval>,
+    max_lens: Vec<usize>,
+    suffix_links: Vec<usize>,
+    text_positions: Vec<usize>,
+    // the transitions of state s are at transition_offsets[s]..transition_offsets[s + 1], sorted by symbol
+    transition_offsets: Vec<usize>,
+    transition_symbols: Vec<I>,
+    transition_targets: Vec<usize>,
+}
+
+impl<I: InputElement> SuffixAutomaton<I> {
+    /// Build the automaton from the virtual suffix tree of `index`.
+    ///
+    /// `text` must be the text for which the suffix array was constructed.
+    ///
+    /// # Panics
+    ///
+    /// If the text does not have the same length as the suffix array or if `index` is a generalized suffix array.
+    pub fn new<O: OutputElement, SaB: BufferMode, LcpB: BufferMode, PlcpB: BufferMode>(
+        index: &SuffixArrayWithLcpAndPlcp<'_, '_, '_, O, SaB, LcpB, PlcpB>,
+        text: &[I],
+    ) -> Self {
+        assert_eq!(
+            text.len(),
+            index.suffix_array().len(),
+            "The text must have the same length as the suffix array"
+        );
+        assert!(
+            !index.is_generalized_suffix_array(),
+            "Generalized suffix arrays are not supported"
+        );
+
+        Builder::new(index.suffix_array(), text).build(index.lcp_intervals())
+    }
+
+    /// The state of the empty string.
+    pub fn initial_state(&self) -> usize {
+        0
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn num_transitions(&self) -> usize {
+        self.transition_targets.len()
+    }
+
+    /// The state of the substrings of the state `state`, extended to the left by `symbol`, if they occur.
+    pub fn transition(&self, state: usize, symbol: I) -> Option<usize> {
+        let range = self.transition_offsets[state]..self.transition_offsets[state + 1];
+
+        self.transition_symbols[range.clone()]
+            .binary_search(&symbol)
+            .ok()
+            .map(|i| self.transition_targets[range.start + i])
+    }
+
+    /// The state of the longest prefix of the substrings of `state` that occurs at more text positions.
+    /// The initial state has no suffix link.
+    pub fn suffix_link(&self, state: usize) -> Option<usize> {
+        Some(self.suffix_links[state]).filter(|&link| link != NO_SUFFIX_LINK)
+    }
+
+    /// The length of the longest substring of `state`.
+    pub fn max_len(&self, state: usize) -> usize {
+        self.max_lens[state]
+    }
+
+    /// The length of the shortest substring of `state`.
+    pub fn min_len(&self, state: usize) -> usize {
+        self.suffix_link(state)
+            .map_or(0, |link| self.max_lens[link] + 1)
+    }
+
+    /// The suffix array interval of the substrings of `state`.
+    pub fn interval(&self, state: usize) -> SuffixArrayInterval {
+        self.intervals[state]
+    }
+
+    /// One text position at which the substrings of `state` occur, which is the suffix array entry at the start
+    /// of its interval. It is 0 for the initial state of the empty text.
+    pub fn text_position(&self, state: usize) -> usize {
+        self.text_positions[state]
+    }
+
+    /// The state of `pattern`, if it is a substring of the text.
+    pub fn state(&self, pattern: &[I]) -> Option<usize> {
+        pattern
+            .iter()
+            .rev()
+            .try_fold(self.initial_state(), |state, &symbol| {
+                self.transition(state, symbol)
+            })
+    }
+
+    /// Whether `pattern` is a substring of the text.
+    pub fn contains(&self, pattern: &[I]) -> bool {
+        self.state(pattern).is_some()
+    }
+
+    /// The range of the leftmost longest substring of `query` that is also a substring of the text.
+    ///
+    /// The query is scanned once from right to left, following suffix links when a match cannot be extended.
+    /// If no symbol of the query occurs in the text, an empty range is returned.
+    pub fn longest_common_factor(&self, query: &[I]) -> Range<usize> {
+        let mut longest = 0..0;
+
+        self.for_each_longest_match(query, |query_start, _, match_len| {
+            if match_len > 0 && match_len >= longest.len() {
+                longest = query_start..query_start + match_len;
+            }
+        });
+
+        longest
+    }
+
+    // Calls f with every position of the query from right to left, the state and the length of the
+    // longest prefix of the query suffix at this position that is a substring of the text.
+    pub(crate) fn for_each_longest_match(
+        &self,
+        query: &[I],
+        mut f: impl FnMut(usize, usize, usize),
+    ) {
+        let mut state = self.initial_state();
+        let mut match_len = 0;
+
+        for (query_start, &symbol) in query.iter().enumerate().rev() {
+            // shorten the match until it can be extended to the left
+            while self.transition(state, symbol).is_none() {
+                match self.suffix_link(state) {
+                    Some(link) => {
+                        state = link;
+                        match_len = self.max_lens[link];
+                    }
+                    None => break,
+                }
+            }
+
+            match self.transition(state, symbol) {
+                Some(target) => {
+                    state = target;
+                    match_len += 1;
+                }
+                None => match_len = 0,
+            }
+
+            f(query_start, state, match_len);
+        }
+    }
+}
+
+// Collects the states in postorder of the virtual suffix tree, except for the initial state.
+struct Builder<'a, I: InputElement, O: OutputElement> {
+    text: &'a [I],
+    suffix_array: &'a [O],
+    inverse_suffix_array: Vec<usize>,
+    intervals: Vec<SuffixArrayInterval>,
+    max_lens: Vec<usize>,
+    suffix_links: Vec<usize>,
+    // for every state the symbols that precede its occurrences and the interval of the extended substrings
+    weiner_links: Vec<Vec<(I, SuffixArrayInterval)>>,
+}
+
+impl<'a, I: InputElement, O: OutputElement> Builder<'a, I, O> {
+    fn new(suffix_array: &'a [O], text: &'a [I]) -> Self {
+        let mut inverse_suffix_array = vec![0; text.len()];
+
+        for (suffix_array_index, &suffix) in suffix_array.iter().enumerate() {
+            inverse_suffix_array[<usize as NumCast>::from(suffix).unwrap()] = suffix_array_index;
+        }
+
+        Self {
+            text,
+            suffix_array,
+            inverse_suffix_array,
+            intervals: vec![SuffixArrayInterval::new(0, text.len())],
+            max_lens: vec![0],
+            suffix_links: vec![NO_SUFFIX_LINK],
+            weiner_links: vec![Vec::new()],
+        }
+    }
+
+    fn build(mut self, lcp_intervals: LcpIntervals<'_, O>) -> SuffixAutomaton<I> {
+        let text_len = self.text.len();
+        let mut leaf_states = vec![None; text_len];
+        let mut internal_states = HashMap::new();
+
+        // the states of the internal nodes whose parents have not been visited yet, with the symbols
+        // that precede their occurrences
+        let mut pending: Vec<(usize, Vec<PrecedingSymbol<I>>)> = Vec::new();
+
+        for lcp_interval in lcp_intervals {
+            let num_internal_children = lcp_interval
+                .child_intervals()
+                .iter()
+                .filter(|child_interval| child_interval.len() > 1)
+                .count();
+            let mut internal_children = pending.split_off(pending.len() - num_internal_children);
+            internal_children.reverse();
+
+            let mut child_states = Vec::new();
+            let mut preceding_symbols = Vec::new();
+
+            for child_interval in lcp_interval.child_intervals() {
+                if child_interval.len() > 1 {
+                    let (state, symbols) = internal_children.pop().unwrap();
+                    child_states.push(state);
+                    preceding_symbols.extend(symbols);
+                } else {
+                    let suffix_array_index = child_interval.start();
+                    let symbols = self.leaf_preceding_symbols(suffix_array_index);
+
+                    // a suffix that is a prefix of another suffix does not have its own state
+                    if self.suffix(suffix_array_index) + lcp_interval.lcp() < text_len {
+                        let state = self.push_state(
+                            *child_interval,
+                            text_len - self.suffix(suffix_array_index),
+                            &symbols,
+                        );
+                        leaf_states[suffix_array_index] = Some(state);
+                        child_states.push(state);
+                    }
+
+                    preceding_symbols.extend(symbols);
+                }
+            }
+
+            let preceding_symbols = merge_preceding_symbols(preceding_symbols);
+
+            // the root with LCP value 0 belongs to the empty string, which is the initial state
+            let state = if lcp_interval.lcp() == 0 {
+                0
+            } else {
+                let state = self.push_state(
+                    lcp_interval.interval(),
+                    lcp_interval.lcp(),
+                    &preceding_symbols,
+                );
+                internal_states.insert(lcp_interval.interval(), state);
+                state
+            };
+
+            for child_state in child_states {
+                self.suffix_links[child_state] = state;
+            }
+
+            pending.push((state, preceding_symbols));
+        }
+
+        // a text of length 1 has no LCP intervals, its single suffix is a leaf of the root
+        if text_len == 1 {
+            let symbols = self.leaf_preceding_symbols(0);
+            let state = self.push_state(SuffixArrayInterval::new(0, 1), 1, &symbols);
+            leaf_states[0] = Some(state);
+        }
+
+        // the children of the root that are not below the root LCP interval
+        for state in 1..self.intervals.len() {
+            if self.suffix_links[state] == NO_SUFFIX_LINK {
+                self.suffix_links[state] = 0;
+            }
+        }
+
+        // the transitions of the initial state lead to the single symbols of the text
+        self.weiner_links[0] = self.symbol_intervals();
+
+        self.resolve_transitions(&leaf_states, &internal_states)
+    }
+
+    fn suffix(&self, suffix_array_index: usize) -> usize {
+        <usize as NumCast>::from(self.suffix_array[suffix_array_index]).unwrap()
+    }
+
+    fn leaf_preceding_symbols(&self, suffix_array_index: usize) -> Vec<PrecedingSymbol<I>> {
+        match self.suffix(suffix_array_index).checked_sub(1) {
+            Some(preceding_suffix) => {
+                let extended = self.inverse_suffix_array[preceding_suffix];
+                vec![(self.text[preceding_suffix], extended, extended)]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn push_state(
+        &mut self,
+        interval: SuffixArrayInterval,
+        max_len: usize,
+        preceding_symbols: &[PrecedingSymbol<I>],
+    ) -> usize {
+        self.intervals.push(interval);
+        self.max_lens.push(max_len);
+        self.suffix_links.push(NO_SUFFIX_LINK);
+        self.weiner_links.push(
+            preceding_symbols
+                .iter()
+                .map(|&(symbol, start, last)| (symbol, SuffixArrayInterval::new(start, last + 1)))
+                .collect(),
+        );
+
+        self.intervals.len() - 1
+    }
+
+    // the suffix array intervals of all symbols of the text, in ascending order
+    fn symbol_intervals(&self) -> Vec<(I, SuffixArrayInterval)> {
+        let mut symbol_intervals: Vec<(I, SuffixArrayInterval)> = Vec::new();
+
+        for suffix_array_index in 0..self.suffix_array.len() {
+            let symbol = self.text[self.suffix(suffix_array_index)];
+
+            match symbol_intervals.last_mut() {
+                Some((last_symbol, interval)) if *last_symbol == symbol => {
+                    *interval = SuffixArrayInterval::new(interval.start(), suffix_array_index + 1);
+                }
+                _ => symbol_intervals.push((
+                    symbol,
+                    SuffixArrayInterval::new(suffix_array_index, suffix_array_index + 1),
+                )),
+            }
+        }
+
+        symbol_intervals
+    }
+
+    // every interval of an extended substring is the interval of exactly one state
+    fn resolve_transitions(
+        self,
+        leaf_states: &[Option<usize>],
+        internal_states: &HashMap<SuffixArrayInterval, usize>,
+    ) -> SuffixAutomaton<I> {
+        let text_positions = self
+            .intervals
+            .iter()
+            .map(|interval| {
+                if interval.is_empty() {
+                    0
+                } else {
+                    self.suffix(interval.start())
+                }
+            })
+            .collect();
+
+        let mut transition_offsets = vec![0];
+        let mut transition_symbols = Vec::new();
+        let mut transition_targets = Vec::new();
+
+        for weiner_links in self.weiner_links {
+            for (symbol, interval) in weiner_links {
+                let target = if interval.len() == 1 {
+                    leaf_states[interval.start()].unwrap()
+                } else {
+                    internal_states[&interval]
+                };
+
+                transition_symbols.push(symbol);
+                transition_targets.push(target);
+            }
+
+            transition_offsets.push(transition_targets.len());
+        }
+
+        SuffixAutomaton {
+            text_positions,
+            intervals: self.intervals,
+            max_lens: self.max_lens,
+            suffix_links: self.suffix_links,
+            transition_offsets,
+            transition_symbols,
+            transition_targets,
+        }
+    }
+}
+
+// sorts by symbol and combines the entries of the same symbol to the smallest and largest index
+fn merge_preceding_symbols<I: InputElement>(
+    mut preceding_symbols: Vec<PrecedingSymbol<I>>,
+) -> Vec<PrecedingSymbol<I>> {
+    preceding_symbols.sort_unstable_by_key(|&(symbol, _, _)| symbol);
+
+    let mut merged: Vec<PrecedingSymbol<I>> = Vec::new();
+
+    for (symbol, start, last) in preceding_symbols {
+        match merged.last_mut() {
+            Some((last_symbol, merged_start, merged_last)) if *last_symbol == symbol => {
+                *merged_start = (*merged_start).min(start);
+                *merged_last = (*merged_last).max(last);
+            }
+            _ => merged.push((symbol, start, last)),
+        }
+    }
+
+    merged
+}
@@ -0,0 +1,276 @@
+/*!
+ * Query any index of this crate through the common [`TextIndex`] trait.
+ *
+ * The index types of this crate have different trade-offs between memory usage and query time, but they all
+ * answer the same basic queries. The [`TextIndex`] trait exposes these queries, such that application code can be
+ * written once and used with any index type:
+ *
+ * * Plain suffix arrays: [`SuffixArrayWithText`], [`LcpLrIndex`], [`EnhancedSuffixArray`] and
+ *   [`PositionRestrictedIndex`].
+ * * Compressed indices: [`CompressedSuffixArray`], [`FmIndex`], [`BidirectionalFmIndex`] and [`RIndex`].
+ *
+ * All intervals are intervals of the suffix array in the convention of `libsais`, so the intervals of different
+ * index types for the same text and pattern are equal, as long as they are not empty.
+ *
+ * ```
+ * use libsais::{
+ *     BwtConstruction, SuffixArrayConstruction, fm_index::FmIndex, text_index::TextIndex,
+ * };
+ *
+ * fn count_all<T: TextIndex<Symbol = u8>>(index: &T, patterns: &[&[u8]]) -> usize {
+ *     patterns.iter().map(|pattern| index.count(pattern)).sum()
+ * }
+ *
+ * let text = b"abracadabra".as_slice();
+ *
+ * let suffix_array = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let bwt = BwtConstruction::for_text(text)
+ *     .with_owned_temporary_array_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ * let fm_index = FmIndex::new(&bwt, 4);
+ *
+ * let patterns = [b"abra".as_slice(), b"a", b"cad"];
+ *
+ * assert_eq!(count_all(&suffix_array, &patterns), 8);
+ * assert_eq!(count_all(&fm_index, &patterns), 8);
+ * assert_eq!(fm_index.extract(7..11), b"abra");
+ * ```
+ *
+ * [`SuffixArrayWithText`]: crate::suffix_array::SuffixArrayWithText
+ * [`LcpLrIndex`]: crate::search::LcpLrIndex
+ * [`EnhancedSuffixArray`]: crate::esa::EnhancedSuffixArray
+ * [`PositionRestrictedIndex`]: crate::position_restricted::PositionRestrictedIndex
+ * [`CompressedSuffixArray`]: crate::csa::CompressedSuffixArray
+ * [`FmIndex`]: crate::fm_index::FmIndex
+ * [`BidirectionalFmIndex`]: crate::bidirectional_fm_index::BidirectionalFmIndex
+ * [`RIndex`]: crate::r_index::RIndex
+ */
+
+use std::ops::Range;
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement, SmallAlphabet,
+    bidirectional_fm_index::BidirectionalFmIndex,
+    csa::CompressedSuffixArray,
+    esa::EnhancedSuffixArray,
+    fm_index::FmIndex,
+    position_restricted::PositionRestrictedIndex,
+    r_index::RIndex,
+    search::{LcpLrIndex, SuffixArrayInterval},
+    suffix_array::SuffixArrayWithText,
+    typestate::BufferMode,
+};
+
+/// The common queries of all index types of this crate.
+///
+/// See [`text_index`](self) for details.
+pub trait TextIndex {
+    /// The element type of the indexed text.
+    type Symbol: InputElement;
+
+    /// The length of the indexed text.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The suffix array interval of all suffixes that start with `pattern`.
+    fn interval(&self, pattern: &[Self::Symbol]) -> SuffixArrayInterval;
+
+    /// The number of occurrences of `pattern` in the text.
+    fn count(&self, pattern: &[Self::Symbol]) -> usize {
+        self.interval(pattern).len()
+    }
+
+    /// The positions of the occurrences of `pattern` in the text, in suffix array order.
+    fn locate(&self, pattern: &[Self::Symbol]) -> Vec<usize>;
+
+    /// The positions of the occurrences of `pattern` in the text, sorted by text position.
+    fn locate_sorted(&self, pattern: &[Self::Symbol]) -> Vec<usize> {
+        let mut positions = self.locate(pattern);
+        positions.sort_unstable();
+        positions
+    }
+
+    /// The substring of the text in the given range.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    fn extract(&self, range: Range<usize>) -> Vec<Self::Symbol>;
+}
+
+impl<'s, 't, I: InputElement, O: OutputElement, B: BufferMode> TextIndex
+    for SuffixArrayWithText<'s, 't, I, O, B>
+{
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        SuffixArrayWithText::interval(self, pattern)
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        SuffixArrayWithText::locate(self, pattern).collect()
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        self.text[range].to_vec()
+    }
+}
+
+impl<'a, I: InputElement, O: OutputElement> TextIndex for LcpLrIndex<'a, I, O> {
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        self.text().len()
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        LcpLrIndex::interval(self, pattern)
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        LcpLrIndex::locate(self, pattern).collect()
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        self.text()[range].to_vec()
+    }
+}
+
+impl<'a, I: InputElement, O: OutputElement> TextIndex for EnhancedSuffixArray<'a, I, O> {
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        EnhancedSuffixArray::len(self)
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        EnhancedSuffixArray::interval(self, pattern)
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        EnhancedSuffixArray::locate(self, pattern).collect()
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        self.text()[range].to_vec()
+    }
+}
+
+impl<'a, I: InputElement, O: OutputElement> TextIndex for PositionRestrictedIndex<'a, I, O> {
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        PositionRestrictedIndex::len(self)
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        PositionRestrictedIndex::interval(self, pattern)
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        self.suffix_array()[self.interval(pattern).range()]
+            .iter()
+            .map(|&suffix| <usize as NumCast>::from(suffix).unwrap())
+            .collect()
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        self.text()[range].to_vec()
+    }
+}
+
+impl<I: InputElement> TextIndex for CompressedSuffixArray<I> {
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        CompressedSuffixArray::len(self)
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        CompressedSuffixArray::interval(self, pattern)
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        CompressedSuffixArray::locate(self, pattern)
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        CompressedSuffixArray::extract(self, range)
+    }
+}
+
+impl<I: SmallAlphabet> TextIndex for FmIndex<I> {
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        FmIndex::len(self)
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        FmIndex::interval(self, pattern)
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        FmIndex::locate(self, pattern)
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        FmIndex::extract(self, range)
+    }
+}
+
+impl<I: SmallAlphabet> TextIndex for BidirectionalFmIndex<I> {
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        BidirectionalFmIndex::len(self)
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        BidirectionalFmIndex::interval(self, pattern).forward()
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        BidirectionalFmIndex::locate(self, pattern)
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        self.forward_index().extract(range)
+    }
+}
+
+impl<I: SmallAlphabet> TextIndex for RIndex<I> {
+    type Symbol = I;
+
+    fn len(&self) -> usize {
+        RIndex::len(self)
+    }
+
+    fn interval(&self, pattern: &[I]) -> SuffixArrayInterval {
+        RIndex::interval(self, pattern)
+    }
+
+    fn locate(&self, pattern: &[I]) -> Vec<usize> {
+        RIndex::locate(self, pattern)
+    }
+
+    fn extract(&self, range: Range<usize>) -> Vec<I> {
+        RIndex::extract(self, range)
+    }
+}
@@ -0,0 +1,307 @@
+/*!
+ * Answer rank, select, access and range queries on sequences over large alphabets using a [wavelet matrix].
+ *
+ * A [`WaveletMatrix`] stores a sequence of `n` integers in `n * ⌈log σ⌉` bits plus the rank information, where `σ` is
+ * the largest value of the sequence. It supports the following queries in `O(log σ)` time:
+ *
+ * * [`WaveletMatrix::access`]: the value at a position.
+ * * [`WaveletMatrix::rank`]: the number of occurrences of a value before a position.
+ * * [`WaveletMatrix::select`]: the position of the `k`-th occurrence of a value (`O(log σ log n)`).
+ *
+ * [`WaveletMatrix::count_less_than`] counts the values of a range that are smaller than a given value in
+ * `O(log σ)` time. Additionally, [`WaveletMatrix::distinct_in_range`] reports all distinct values of a range
+ * together with their frequencies in `O(d log σ)` time, where `d` is the number of distinct values.
+ * [`WaveletMatrix::distinct_in_value_range`] does the same, but only for the values in a given value range.
+ *
+ * The main use case is the BWT of a text over a `u16` alphabet, where a plain occurrence table for every symbol
+ * would be far too large. The [`FmIndex`](super::fm_index::FmIndex) uses a wavelet matrix automatically for texts
+ * with many distinct symbols.
+ *
+ * ```
+ * use libsais::{BwtConstruction, wavelet_matrix::WaveletMatrix};
+ *
+ * let text: Vec<u16> = vec![700, 3, 700, 42, 3, 700, 1000];
+ *
+ * let bwt = BwtConstruction::for_text(&text)
+ *     .with_owned_temporary_array_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * let wavelet_matrix = WaveletMatrix::new(bwt.bwt());
+ *
+ * assert_eq!(wavelet_matrix.access(3), bwt.bwt()[3]);
+ * assert_eq!(wavelet_matrix.rank(700, text.len()), 3);
+ * assert_eq!(wavelet_matrix.select(3, 2), None);
+ * assert_eq!(wavelet_matrix.distinct_in_range(0, text.len()).len(), 4);
+ * ```
+ *
+ * [wavelet matrix]: https://doi.org/10.1016/j.is.2014.06.002
+ */
+
+use std::ops::Range;
+
+use num_traits::{NumCast, PrimInt};
+
+use crate::bitvec::BitVector;
+
+/// A wavelet matrix over a sequence of non-negative integers.
+///
+/// See [`wavelet_matrix`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WaveletMatrix<T: PrimInt> {
+    len: usize,
+    // levels[0] contains the most significant bits
+    levels: Vec<BitVector>,
+    num_zeros: Vec<usize>,
+    _value_type: std::marker::PhantomData<T>,
+}
+
+impl<T: PrimInt> WaveletMatrix<T> {
+    /// # Panics
+    ///
+    /// If the sequence contains a negative value.
+    pub fn new(values: &[T]) -> Self {
+        let mut current: Vec<u64> = values
+            .iter()
+            .map(|&value| {
+                <u64 as NumCast>::from(value)
+                    .expect("Values of a wavelet matrix cannot be negative")
+            })
+            .collect();
+
+        let max_value = current.iter().copied().max().unwrap_or(0);
+        let num_levels = (u64::BITS - max_value.leading_zeros()) as usize;
+
+        let mut levels = Vec::with_capacity(num_levels);
+        let mut num_zeros = Vec::with_capacity(num_levels);
+
+        for level in 0..num_levels {
+            let bit = num_levels - 1 - level;
+
+            levels.push(BitVector::from_ones(
+                current.len(),
+                current
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, value)| value >> bit & 1 == 1)
+                    .map(|(i, _)| i),
+            ));
+
+            // stable partition by the current bit, values with a zero bit come first
+            let (zeros, ones): (Vec<_>, Vec<_>) =
+                current.into_iter().partition(|value| value >> bit & 1 == 0);
+            num_zeros.push(zeros.len());
+
+            current = zeros;
+            current.extend(ones);
+        }
+
+        Self {
+            len: values.len(),
+            levels,
+            num_zeros,
+            _value_type: std::marker::PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The value at position `i`.
+    ///
+    /// # Panics
+    ///
+    /// If the position is out of bounds.
+    pub fn access(&self, mut i: usize) -> T {
+        assert!(i < self.len, "Index out of bounds");
+
+        let mut value = 0u64;
+
+        for (level, bits) in self.levels.iter().enumerate() {
+            let bit = bits.get(i);
+            value = value << 1 | bit as u64;
+            i = self.follow(level, i, bit);
+        }
+
+        <T as NumCast>::from(value).unwrap()
+    }
+
+    /// The number of occurrences of `value` in the range `[0, i)`.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is greater than the length of the sequence.
+    pub fn rank(&self, value: T, i: usize) -> usize {
+        assert!(i <= self.len, "Index out of bounds");
+
+        self.range_of_value(value, 0, i)
+            .map_or(0, |(start, end)| end - start)
+    }
+
+    /// The position of the occurrence of `value` with the given `rank` (0-based), if it exists.
+    pub fn select(&self, value: T, rank: usize) -> Option<usize> {
+        let (start, end) = self.range_of_value(value, 0, self.len)?;
+
+        if rank >= end - start {
+            return None;
+        }
+
+        let value = <u64 as NumCast>::from(value).unwrap();
+        let mut i = start + rank;
+
+        for (level, bits) in self.levels.iter().enumerate().rev() {
+            let bit = self.bit_of_value(value, level);
+
+            i = if bit {
+                bits.select1(i - self.num_zeros[level])
+            } else {
+                bits.select0(i)
+            };
+        }
+
+        Some(i)
+    }
+
+    /// The number of values smaller than `value` in the range `[start, end)`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    pub fn count_less_than(&self, value: T, mut start: usize, mut end: usize) -> usize {
+        assert!(start <= end && end <= self.len, "Range out of bounds");
+
+        let Some(value) = <u64 as NumCast>::from(value) else {
+            return 0;
+        };
+
+        if value.checked_shr(self.levels.len() as u32).unwrap_or(0) != 0 {
+            return end - start;
+        }
+
+        let mut count = 0;
+
+        for level in 0..self.levels.len() {
+            let bit = self.bit_of_value(value, level);
+
+            if bit {
+                count += self.levels[level].rank0(end) - self.levels[level].rank0(start);
+            }
+
+            start = self.follow(level, start, bit);
+            end = self.follow(level, end, bit);
+        }
+
+        count
+    }
+
+    /// All distinct values in the range `[start, end)` with their number of occurrences, in ascending order.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    pub fn distinct_in_range(&self, start: usize, end: usize) -> Vec<(T, usize)> {
+        assert!(start <= end && end <= self.len, "Range out of bounds");
+
+        let mut distinct = Vec::new();
+        self.collect_distinct(0, 0, start, end, (0, u128::MAX), &mut distinct);
+
+        distinct
+    }
+
+    /// All distinct values of the range `[start, end)` that are contained in `value_range`, with their number
+    /// of occurrences, in ascending order.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds.
+    pub fn distinct_in_value_range(
+        &self,
+        start: usize,
+        end: usize,
+        value_range: Range<T>,
+    ) -> Vec<(T, usize)> {
+        assert!(start <= end && end <= self.len, "Range out of bounds");
+
+        // negative bounds are clamped to 0, because the matrix does not contain negative values
+        let to_bound = |value: T| <u128 as NumCast>::from(value.max(T::zero())).unwrap();
+        let value_bounds = (to_bound(value_range.start), to_bound(value_range.end));
+
+        let mut distinct = Vec::new();
+        self.collect_distinct(0, 0, start, end, value_bounds, &mut distinct);
+
+        distinct
+    }
+
+    // collects the distinct values of the node with the given prefix that are in the half-open
+    // value bounds
+    fn collect_distinct(
+        &self,
+        level: usize,
+        prefix: u64,
+        start: usize,
+        end: usize,
+        value_bounds: (u128, u128),
+        distinct: &mut Vec<(T, usize)>,
+    ) {
+        let num_remaining_levels = self.levels.len() - level;
+        let node_min_value = (prefix as u128) << num_remaining_levels;
+        let node_end_value = (prefix as u128 + 1) << num_remaining_levels;
+
+        if start == end || node_end_value <= value_bounds.0 || node_min_value >= value_bounds.1 {
+            return;
+        }
+
+        if level == self.levels.len() {
+            distinct.push((<T as NumCast>::from(prefix).unwrap(), end - start));
+            return;
+        }
+
+        for bit in [false, true] {
+            self.collect_distinct(
+                level + 1,
+                prefix << 1 | bit as u64,
+                self.follow(level, start, bit),
+                self.follow(level, end, bit),
+                value_bounds,
+                distinct,
+            );
+        }
+    }
+
+    // the range after the last level that corresponds to the occurrences of value in [start, end)
+    // of the first level, None if the value is too large for the matrix
+    fn range_of_value(&self, value: T, mut start: usize, mut end: usize) -> Option<(usize, usize)> {
+        let value = <u64 as NumCast>::from(value)?;
+
+        if value.checked_shr(self.levels.len() as u32).unwrap_or(0) != 0 {
+            return None;
+        }
+
+        for level in 0..self.levels.len() {
+            let bit = self.bit_of_value(value, level);
+            start = self.follow(level, start, bit);
+            end = self.follow(level, end, bit);
+        }
+
+        Some((start, end))
+    }
+
+    fn bit_of_value(&self, value: u64, level: usize) -> bool {
+        value >> (self.levels.len() - 1 - level) & 1 == 1
+    }
+
+    // maps a position of a level to the position in the next level
+    fn follow(&self, level: usize, i: usize, bit: bool) -> usize {
+        if bit {
+            self.num_zeros[level] + self.levels[level].rank1(i)
+        } else {
+            self.levels[level].rank0(i)
+        }
+    }
+}
@@ -0,0 +1,449 @@
+/*!
+ * Search for patterns with character classes and wildcards using a suffix array.
+ *
+ * A [`WildcardPattern`] is a sequence of [`PatternElement`]s, which are single symbols, character classes, the
+ * wildcard `?` for any single symbol and the wildcard `*` for any sequence of symbols. For `u8`-based texts,
+ * patterns can be parsed from a simple syntax using [`WildcardPattern::parse`] or from degenerate nucleotide
+ * sequences using [`WildcardPattern::from_iupac`].
+ *
+ * The pattern is compiled to a nondeterministic finite automaton with one state per pattern element. The search
+ * walks the trie of all substrings of the text, which is implicitly given by the suffix array, and simulates the
+ * automaton along the way. The intervals of the children of a node are found by binary search, so only the
+ * parts of the text that can still match are ever visited. Branches of the trie are pruned as soon as the
+ * automaton has no active state left.
+ *
+ * Every distinct substring of the text that matches the pattern, but does not have a proper prefix that
+ * matches the pattern, is reported as a [`WildcardMatch`]. Therefore, every text position at which a match
+ * starts is contained in exactly one reported match. If the pattern matches the empty sequence, e.g. `*`, the
+ * only reported match is the empty substring with a [`WildcardMatch::match_len`] of 0, which occurs at every
+ * text position. An empty text has no matches.
+ *
+ * ```
+ * use libsais::{SuffixArrayConstruction, wildcard::WildcardPattern};
+ *
+ * let text = b"ACGTTGCAACGTAGCTAGCAT".as_slice();
+ *
+ * let res = SuffixArrayConstruction::for_text(text)
+ *     .in_owned_buffer32()
+ *     .single_threaded()
+ *     .run()
+ *     .unwrap();
+ *
+ * // IUPAC code R stands for A or G
+ * let pattern = WildcardPattern::from_iupac(b"AGCR").unwrap();
+ * let matches = res.wildcard_matches(&pattern);
+ *
+ * assert_eq!(matches.len(), 1);
+ * assert_eq!(matches[0].positions(), &[16]);
+ *
+ * let pattern = WildcardPattern::parse(b"C?[AT]*GC").unwrap();
+ * let mut positions: Vec<_> = res
+ *     .wildcard_matches(&pattern)
+ *     .iter()
+ *     .flat_map(|m| m.positions().to_vec())
+ *     .collect();
+ * positions.sort();
+ *
+ * assert_eq!(positions, vec![1, 6, 9, 14]);
+ * ```
+ *
+ * # Sentinel Convention
+ *
+ * Matches never extend beyond the end of the text. For generalized suffix arrays, the zero separators are only
+ * matched by pattern elements that explicitly contain the zero symbol, so matches of wildcards and negated
+ * classes never span multiple texts of the set.
+ */
+
+use std::fmt::{Debug, Display};
+
+use num_traits::NumCast;
+
+use crate::{
+    InputElement, OutputElement,
+    search::{SuffixArrayInterval, child_intervals, refine_interval},
+    suffix_array::SuffixArrayWithText,
+    typestate::BufferMode,
+};
+
+/// A single element of a [`WildcardPattern`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatternElement<I> {
+    /// Matches exactly the given symbol.
+    Symbol(I),
+    /// Matches any of the given symbols.
+    Class(Vec<I>),
+    /// Matches any symbol except the given ones.
+    NegatedClass(Vec<I>),
+    /// Matches any single symbol (`?`).
+    Any,
+    /// Matches any sequence of symbols, including the empty sequence (`*`).
+    AnySequence,
+}
+
+impl<I: InputElement> PatternElement<I> {
+    fn matches(&self, symbol: I, is_generalized_suffix_array: bool) -> bool {
+        let is_separator = is_generalized_suffix_array && symbol == I::zero();
+
+        match self {
+            Self::Symbol(expected) => symbol == *expected,
+            Self::Class(symbols) => symbols.contains(&symbol),
+            Self::NegatedClass(symbols) => !is_separator && !symbols.contains(&symbol),
+            Self::Any | Self::AnySequence => !is_separator,
+        }
+    }
+}
+
+/// A pattern with character classes and wildcards.
+///
+/// See [`wildcard`](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WildcardPattern<I> {
+    elements: Vec<PatternElement<I>>,
+}
+
+impl<I: InputElement> WildcardPattern<I> {
+    pub fn new(elements: Vec<PatternElement<I>>) -> Self {
+        Self { elements }
+    }
+
+    pub fn elements(&self) -> &[PatternElement<I>] {
+        &self.elements
+    }
+}
+
+impl WildcardPattern<u8> {
+    /// Parse a pattern from a simple regex-like syntax.
+    ///
+    /// * `?` matches any single symbol and `*` matches any sequence of symbols.
+    /// * `[...]` matches any of the contained symbols and `[^...]` any symbol except the contained ones.
+    ///   Classes can contain ranges like `a-z`. A `-` at the start or end of a class is a literal.
+    /// * `\` escapes the following symbol, both inside and outside of classes.
+    /// * All other symbols match themselves.
+    pub fn parse(pattern: &[u8]) -> Result<Self, WildcardPatternError> {
+        let mut elements = Vec::new();
+        let mut position = 0;
+
+        while position < pattern.len() {
+            let element = match pattern[position] {
+                b'?' => PatternElement::Any,
+                b'*' => PatternElement::AnySequence,
+                b'\\' => {
+                    position += 1;
+                    let &symbol = pattern
+                        .get(position)
+                        .ok_or(WildcardPatternError::TrailingEscape)?;
+                    PatternElement::Symbol(symbol)
+                }
+                b'[' => {
+                    let (element, class_end) = parse_class(pattern, position)?;
+                    position = class_end;
+                    element
+                }
+                symbol => PatternElement::Symbol(symbol),
+            };
+
+            elements.push(element);
+            position += 1;
+        }
+
+        Ok(Self::new(elements))
+    }
+
+    /// Create a pattern from a nucleotide sequence with [IUPAC codes] in upper case.
+    ///
+    /// The codes are translated to classes of the symbols `A`, `C`, `G` and `T`.
+    ///
+    /// [IUPAC codes]: https://www.bioinformatics.org/sms/iupac.html
+    pub fn from_iupac(pattern: &[u8]) -> Result<Self, WildcardPatternError> {
+        pattern
+            .iter()
+            .enumerate()
+            .map(|(position, &code)| {
+                let symbols: &[u8] = match code {
+                    b'A' | b'C' | b'G' | b'T' => return Ok(PatternElement::Symbol(code)),
+                    b'U' => return Ok(PatternElement::Symbol(b'T')),
+                    b'R' => b"AG",
+                    b'Y' => b"CT",
+                    b'S' => b"CG",
+                    b'W' => b"AT",
+                    b'K' => b"GT",
+                    b'M' => b"AC",
+                    b'B' => b"CGT",
+                    b'D' => b"AGT",
+                    b'H' => b"ACT",
+                    b'V' => b"ACG",
+                    b'N' => b"ACGT",
+                    _ => return Err(WildcardPatternError::InvalidIupacCode { position }),
+                };
+
+                Ok(PatternElement::Class(symbols.to_vec()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self::new)
+    }
+}
+
+/// The error type for parsing a [`WildcardPattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WildcardPatternError {
+    /// A character class that starts at the given position is not closed.
+    UnclosedClass { position: usize },
+    /// A character class that starts at the given position does not contain any symbol.
+    EmptyClass { position: usize },
+    /// A range in a character class that starts at the given position is reversed, such as `z-a`.
+    InvalidRange { position: usize },
+    /// The pattern ends with an unescaped `\`.
+    TrailingEscape,
+    /// The symbol at the given position is not an IUPAC nucleotide code.
+    InvalidIupacCode { position: usize },
+}
+
+impl Display for WildcardPatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <WildcardPatternError as Debug>::fmt(self, f)
+    }
+}
+
+impl std::error::Error for WildcardPatternError {}
+
+/// A distinct substring of the text that matches a [`WildcardPattern`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WildcardMatch {
+    interval: SuffixArrayInterval,
+    positions: Vec<usize>,
+    match_len: usize,
+}
+
+impl WildcardMatch {
+    /// The suffix array interval of the matched substring.
+    pub fn interval(&self) -> SuffixArrayInterval {
+        self.interval
+    }
+
+    /// The positions of the occurrences of the matched substring, sorted by text position.
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// The length of the matched substring.
+    pub fn match_len(&self) -> usize {
+        self.match_len
+    }
+}
+
+impl<'s, 't, I: InputElement, O: OutputElement, B: BufferMode>
+    SuffixArrayWithText<'s, 't, I, O, B>
+{
+    /// All distinct substrings of the text that match `pattern` and do not have a proper prefix that matches
+    /// `pattern`, ordered by their suffix array interval.
+    ///
+    /// If `pattern` matches the empty sequence, the empty substring is reported as the only match, at every
+    /// position of the text. Matches without an occurrence are never reported, so the result for an empty text
+    /// is empty.
+    pub fn wildcard_matches(&self, pattern: &WildcardPattern<I>) -> Vec<WildcardMatch> {
+        let text = self.text;
+        let suffix_array = &self.suffix_array.buffer;
+        let automaton = Automaton {
+            elements: &pattern.elements,
+            is_generalized_suffix_array: self.is_generalized_suffix_array,
+        };
+
+        let mut matches = Vec::new();
+
+        // explicit stack instead of recursion, because patterns with * can lead to deep traversals
+        let mut stack = vec![(
+            SuffixArrayInterval::new(0, text.len()),
+            0,
+            automaton.initial_states(),
+        )];
+
+        while let Some((interval, depth, states)) = stack.pop() {
+            // only the root interval of an empty text can be empty
+            if interval.is_empty() {
+                continue;
+            }
+
+            if automaton.is_accepting(&states) {
+                let mut positions: Vec<_> = suffix_array[interval.range()]
+                    .iter()
+                    .map(|&suffix| <usize as NumCast>::from(suffix).unwrap())
+                    .collect();
+                positions.sort_unstable();
+
+                matches.push(WildcardMatch {
+                    interval,
+                    positions,
+                    match_len: depth,
+                });
+
+                continue;
+            }
+
+            let children = match automaton.candidate_symbols(&states) {
+                Some(symbols) => symbols
+                    .into_iter()
+                    .map(|symbol| {
+                        let child = refine_interval(text, suffix_array, interval, depth, symbol);
+                        (symbol, child)
+                    })
+                    .filter(|(_, child)| !child.is_empty())
+                    .collect(),
+                None => child_intervals(text, suffix_array, interval, depth),
+            };
+
+            for (symbol, child) in children {
+                let child_states = automaton.step(&states, symbol);
+
+                if child_states.contains(&true) {
+                    stack.push((child, depth + 1, child_states));
+                }
+            }
+        }
+
+        matches.sort_unstable_by_key(|m| m.interval);
+
+        matches
+    }
+}
+
+// Nondeterministic automaton with one state per pattern element and an additional accepting state.
+// Being in state i means that the pattern elements before i were matched.
+struct Automaton<'p, I> {
+    elements: &'p [PatternElement<I>],
+    is_generalized_suffix_array: bool,
+}
+
+impl<'p, I: InputElement> Automaton<'p, I> {
+    fn initial_states(&self) -> Vec<bool> {
+        let mut states = vec![false; self.elements.len() + 1];
+        states[0] = true;
+        self.add_empty_transitions(&mut states);
+
+        states
+    }
+
+    fn is_accepting(&self, states: &[bool]) -> bool {
+        states[self.elements.len()]
+    }
+
+    fn step(&self, states: &[bool], symbol: I) -> Vec<bool> {
+        let mut next_states = vec![false; states.len()];
+
+        for (state, element) in self.elements.iter().enumerate() {
+            if !states[state] || !element.matches(symbol, self.is_generalized_suffix_array) {
+                continue;
+            }
+
+            match element {
+                PatternElement::AnySequence => next_states[state] = true,
+                _ => next_states[state + 1] = true,
+            }
+        }
+
+        self.add_empty_transitions(&mut next_states);
+
+        next_states
+    }
+
+    // * can match the empty sequence, the transitions only go forward, so one pass is enough
+    fn add_empty_transitions(&self, states: &mut [bool]) {
+        for (state, element) in self.elements.iter().enumerate() {
+            if states[state] && *element == PatternElement::AnySequence {
+                states[state + 1] = true;
+            }
+        }
+    }
+
+    // the sorted symbols that can lead to a transition, None if all symbols can
+    fn candidate_symbols(&self, states: &[bool]) -> Option<Vec<I>> {
+        let mut symbols = Vec::new();
+
+        for (state, element) in self.elements.iter().enumerate() {
+            if !states[state] {
+                continue;
+            }
+
+            match element {
+                PatternElement::Symbol(symbol) => symbols.push(*symbol),
+                PatternElement::Class(class_symbols) => symbols.extend_from_slice(class_symbols),
+                _ => return None,
+            }
+        }
+
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        Some(symbols)
+    }
+}
+
+// parses the class starting at class_start and returns the position of its closing bracket
+fn parse_class(
+    pattern: &[u8],
+    class_start: usize,
+) -> Result<(PatternElement<u8>, usize), WildcardPatternError> {
+    let mut position = class_start + 1;
+    let is_negated = pattern.get(position) == Some(&b'^');
+
+    if is_negated {
+        position += 1;
+    }
+
+    let mut symbols = Vec::new();
+
+    loop {
+        let symbol = match pattern.get(position) {
+            None => {
+                return Err(WildcardPatternError::UnclosedClass {
+                    position: class_start,
+                });
+            }
+            Some(b']') => break,
+            Some(b'\\') => {
+                position += 1;
+                *pattern
+                    .get(position)
+                    .ok_or(WildcardPatternError::TrailingEscape)?
+            }
+            Some(&symbol) => symbol,
+        };
+
+        let is_range = pattern.get(position + 1) == Some(&b'-')
+            && pattern
+                .get(position + 2)
+                .is_some_and(|&range_end| range_end != b']');
+
+        if is_range {
+            let range_end = pattern[position + 2];
+
+            if range_end < symbol {
+                return Err(WildcardPatternError::InvalidRange {
+                    position: class_start,
+                });
+            }
+
+            symbols.extend(symbol..=range_end);
+            position += 3;
+        } else {
+            symbols.push(symbol);
+            position += 1;
+        }
+    }
+
+    if symbols.is_empty() {
+        return Err(WildcardPatternError::EmptyClass {
+            position: class_start,
+        });
+    }
+
+    symbols.sort_unstable();
+    symbols.dedup();
+
+    let element = if is_negated {
+        PatternElement::NegatedClass(symbols)
+    } else {
+        PatternElement::Class(symbols)
+    };
+
+    Ok((element, position))
+}
@@ -0,0 +1,173 @@
+use libsais::{
+    BwtConstruction, SuffixArrayConstruction,
+    approximate::{ApproximateMatch, Distance},
+    fm_index::FmIndex,
+};
+
+mod common;
+
+use common::*;
+
+fn edit_distance<I: PartialEq>(a: &[I], b: &[I]) -> usize {
+    let mut column: Vec<_> = (0..=b.len()).collect();
+
+    for (i, x) in a.iter().enumerate() {
+        let mut next_column = vec![i + 1];
+
+        for (j, y) in b.iter().enumerate() {
+            next_column.push(
+                (column[j] + (x != y) as usize)
+                    .min(column[j + 1] + 1)
+                    .min(next_column[j] + 1),
+            );
+        }
+
+        column = next_column;
+    }
+
+    column[b.len()]
+}
+
+fn hamming_distance<I: PartialEq>(a: &[I], b: &[I]) -> Option<usize> {
+    (a.len() == b.len()).then(|| a.iter().zip(b).filter(|(x, y)| x != y).count())
+}
+
+// (substring, sorted positions, cost) of all distinct substrings within the maximum cost
+fn naive_matches<I: libsais::InputElement>(
+    text: &[I],
+    pattern: &[I],
+    max_cost: usize,
+    distance: Distance,
+) -> Vec<(Vec<I>, Vec<usize>, usize)> {
+    let mut substrings: Vec<Vec<I>> = (0..text.len())
+        .flat_map(|start| (start + 1..=text.len()).map(move |end| text[start..end].to_vec()))
+        .collect();
+    substrings.sort_unstable();
+    substrings.dedup();
+
+    let mut matches: Vec<_> = substrings
+        .into_iter()
+        .filter_map(|substring| {
+            let cost = match distance {
+                Distance::Hamming => hamming_distance(&substring, pattern)?,
+                Distance::Edit => edit_distance(&substring, pattern),
+            };
+
+            (cost <= max_cost).then(|| {
+                let positions = naive_occurrences(text, &substring);
+                (substring, positions, cost)
+            })
+        })
+        .collect();
+    matches.sort_unstable();
+
+    matches
+}
+
+fn matches_to_comparable<I: Ord + Copy>(
+    text: &[I],
+    matches: &[ApproximateMatch],
+) -> Vec<(Vec<I>, Vec<usize>, usize)> {
+    let mut comparable: Vec<_> = matches
+        .iter()
+        .map(|m| {
+            let start = m.positions()[0];
+            assert_eq!(m.interval().len(), m.positions().len());
+
+            (
+                text[start..start + m.match_len()].to_vec(),
+                m.positions().to_vec(),
+                m.cost(),
+            )
+        })
+        .collect();
+    comparable.sort_unstable();
+
+    comparable
+}
+
+#[test]
+fn approximate_matches_suffix_array() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    for pattern in [b"".as_slice(), b"a", b"abc", b"bbab", b"cab", b"ccc"] {
+        for max_cost in 0..=2 {
+            for distance in [Distance::Hamming, Distance::Edit] {
+                let matches = res.approximate_matches(pattern, max_cost, distance);
+
+                assert_eq!(
+                    matches_to_comparable(text, &matches),
+                    naive_matches(text, pattern, max_cost, distance)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn approximate_matches_fm_index_agrees_with_suffix_array() {
+    let text = b"ACGTTGCAACGTAGCTAGCTTTACGACGACGTTAG".as_slice();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = FmIndex::new(&bwt, 3);
+
+    for pattern in [b"ACGT".as_slice(), b"TAGC", b"GGGG", b"ACGACG"] {
+        for max_cost in 0..=2 {
+            for distance in [Distance::Hamming, Distance::Edit] {
+                let matches = index.approximate_matches(pattern, max_cost, distance);
+
+                assert_eq!(
+                    matches,
+                    res.approximate_matches(pattern, max_cost, distance)
+                );
+                assert_eq!(
+                    matches_to_comparable(text, &matches),
+                    naive_matches(text, pattern, max_cost, distance)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn approximate_matches_u16() {
+    let text: Vec<u16> = (0..120u32)
+        .map(|i| ((i * 7 + i / 5) % 11) as u16 * 1000)
+        .collect();
+
+    let bwt = BwtConstruction::for_text(&text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = FmIndex::new(&bwt, 4);
+
+    let pattern = [7000, 3000, 10000];
+
+    for distance in [Distance::Hamming, Distance::Edit] {
+        let matches = index.approximate_matches(&pattern, 1, distance);
+
+        assert_eq!(
+            matches_to_comparable(&text, &matches),
+            naive_matches(&text, &pattern, 1, distance)
+        );
+    }
+}
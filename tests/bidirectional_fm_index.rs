@@ -0,0 +1,143 @@
+use libsais::{
+    BwtConstruction,
+    bidirectional_fm_index::{BidirectionalFmIndex, BidirectionalInterval},
+    fm_index::FmIndex,
+};
+
+mod common;
+
+use common::*;
+
+fn all_substrings<I: Copy>(text: &[I], max_len: usize) -> Vec<Vec<I>> {
+    (0..text.len())
+        .flat_map(|start| {
+            (start + 1..=(start + max_len).min(text.len())).map(move |end| (start, end))
+        })
+        .map(|(start, end)| text[start..end].to_vec())
+        .collect()
+}
+
+// extends the pattern starting from the symbol at split, first to the right and then to the left
+fn search_from_middle<I: libsais::SmallAlphabet>(
+    index: &BidirectionalFmIndex<I>,
+    pattern: &[I],
+    split: usize,
+) -> BidirectionalInterval {
+    let mut interval = index.root_interval();
+
+    for &symbol in &pattern[split..] {
+        interval = index.extend_right(interval, symbol);
+    }
+
+    for &symbol in pattern[..split].iter().rev() {
+        interval = index.extend_left(interval, symbol);
+    }
+
+    interval
+}
+
+#[test]
+fn empty_text_bidirectional_fm_index() {
+    let index =
+        BidirectionalFmIndex::new(&[] as &[u8], 3).expect("libsais should run without an error");
+
+    assert!(index.is_empty());
+    assert!(index.root_interval().is_empty());
+    assert!(index.extend_right(index.root_interval(), b'a').is_empty());
+    assert_eq!(index.count(b"a"), 0);
+}
+
+#[test]
+fn bidirectional_extension_orders() {
+    let (text, _, _, _) = setup_basic_example();
+    let index = BidirectionalFmIndex::new(text, 3).expect("libsais should run without an error");
+
+    let reversed_text: Vec<_> = text.iter().rev().copied().collect();
+
+    for pattern in all_substrings(text, 6)
+        .into_iter()
+        .chain([b"cc".to_vec(), b"abc".to_vec()])
+    {
+        let expected = naive_occurrences(text, &pattern);
+        let reversed_pattern: Vec<_> = pattern.iter().rev().copied().collect();
+
+        let interval = index.interval(&pattern);
+
+        assert_eq!(interval.len(), expected.len());
+        assert_eq!(interval.pattern_len(), pattern.len());
+        assert_eq!(index.locate_sorted(&pattern), expected);
+
+        for split in 0..=pattern.len() {
+            let other = search_from_middle(&index, &pattern, split);
+
+            assert_eq!(other.len(), expected.len());
+
+            if !expected.is_empty() {
+                assert_eq!(other, interval);
+                assert_eq!(other.forward(), index.forward_index().interval(&pattern));
+                assert_eq!(
+                    other.reverse(),
+                    index.reverse_index().interval(&reversed_pattern)
+                );
+                assert_eq!(
+                    naive_occurrences(&reversed_text, &reversed_pattern).len(),
+                    other.len()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn bidirectional_from_fm_indices() {
+    let text = b"mississippi".as_slice();
+    let reversed_text: Vec<_> = text.iter().rev().copied().collect();
+
+    let forward_bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+    let reverse_bwt = BwtConstruction::for_text(&reversed_text)
+        .with_owned_temporary_array_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = BidirectionalFmIndex::from_fm_indices(
+        FmIndex::new(&forward_bwt, 2),
+        FmIndex::new(&reverse_bwt, 2),
+    );
+
+    assert_eq!(index, BidirectionalFmIndex::new(text, 2).unwrap());
+
+    let interval = search_from_middle(&index, b"ssi", 1);
+    assert_eq!(index.locate_interval(interval).len(), 2);
+    assert_eq!(index.locate_sorted(b"ssi"), vec![2, 5]);
+    assert_eq!(
+        index.locate_sorted(b""),
+        (0..text.len()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn bidirectional_large_alphabet() {
+    let text: Vec<u16> = (0..600u32)
+        .map(|i| ((i * 13 + i / 17) % 300) as u16 * 100)
+        .collect();
+
+    let index = BidirectionalFmIndex::new(&text, 5).expect("libsais should run without an error");
+
+    for pattern in all_substrings(&text[100..160], 3) {
+        let expected = naive_occurrences(&text, &pattern);
+
+        for split in [0, pattern.len() / 2, pattern.len()] {
+            let interval = search_from_middle(&index, &pattern, split);
+
+            assert_eq!(interval.len(), expected.len());
+            assert_eq!(index.locate_interval(interval).len(), expected.len());
+        }
+
+        assert_eq!(index.locate_sorted(&pattern), expected);
+    }
+}
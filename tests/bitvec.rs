@@ -0,0 +1,100 @@
+use libsais::bitvec::{BitVector, FromBytesError};
+
+fn check_rank_and_select(bits: &[bool]) {
+    let bitvec: BitVector = bits.iter().copied().collect();
+
+    assert_eq!(bitvec.len(), bits.len());
+    assert_eq!(
+        bitvec,
+        BitVector::from_ones(bits.len(), (0..bits.len()).filter(|&i| bits[i]))
+    );
+
+    let mut ones = 0;
+    let mut zeros = 0;
+
+    for (i, &bit) in bits.iter().enumerate() {
+        assert_eq!(bitvec.get(i), bit);
+        assert_eq!(bitvec.rank1(i), ones);
+        assert_eq!(bitvec.rank0(i), zeros);
+
+        if bit {
+            assert_eq!(bitvec.select1(ones), i);
+            ones += 1;
+        } else {
+            assert_eq!(bitvec.select0(zeros), i);
+            zeros += 1;
+        }
+    }
+
+    assert_eq!(bitvec.rank1(bits.len()), ones);
+    assert_eq!(bitvec.count_ones(), ones);
+    assert_eq!(bitvec.count_zeros(), zeros);
+}
+
+#[test]
+fn empty_bitvec() {
+    let bitvec = BitVector::from_ones(0, []);
+
+    assert!(bitvec.is_empty());
+    assert_eq!(bitvec.rank1(0), 0);
+    assert_eq!(bitvec.count_zeros(), 0);
+    assert_eq!(BitVector::from_bytes(&bitvec.to_bytes()), Ok(bitvec));
+}
+
+#[test]
+fn rank_and_select_different_densities() {
+    // pseudo random bits spanning several blocks and select samples
+    for density in [1, 10, 50, 90, 99] {
+        for len in [1, 63, 64, 65, 511, 512, 4000] {
+            let bits: Vec<bool> = (0..len)
+                .map(|i: usize| (i * 7919 + 13) % 97 < density)
+                .collect();
+
+            check_rank_and_select(&bits);
+        }
+    }
+
+    // very uneven distributions
+    let mut bits = vec![false; 5000];
+    bits.extend(vec![true; 3000]);
+    bits.extend(vec![false; 70]);
+    bits.push(true);
+    check_rank_and_select(&bits);
+}
+
+#[test]
+#[should_panic]
+fn select_out_of_bounds() {
+    BitVector::from_ones(100, [3, 50]).select1(2);
+}
+
+#[test]
+fn bitvec_serialization() {
+    let bitvec = BitVector::from_ones(1000, (0..1000).step_by(3));
+    let bytes = bitvec.to_bytes();
+
+    assert_eq!(bytes.len(), 8 + 16 * 8);
+    assert_eq!(BitVector::from_bytes(&bytes), Ok(bitvec));
+
+    assert_eq!(
+        BitVector::from_bytes(&bytes[..5]),
+        Err(FromBytesError::UnexpectedLength {
+            expected: 8,
+            actual: 5
+        })
+    );
+    assert_eq!(
+        BitVector::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(FromBytesError::UnexpectedLength {
+            expected: bytes.len(),
+            actual: bytes.len() - 1
+        })
+    );
+
+    let mut with_bit_after_end = bytes.clone();
+    *with_bit_after_end.last_mut().unwrap() |= 0x80;
+    assert_eq!(
+        BitVector::from_bytes(&with_bit_after_end),
+        Err(FromBytesError::BitAfterEnd)
+    );
+}
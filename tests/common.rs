@@ -256,3 +256,9 @@ pub fn is_libsais_plcp<O: OutputElement>(suffix_array: &[O], plcp: &[O], lcp: &[
 
     true
 }
+
+pub fn naive_occurrences<I: InputElement>(text: &[I], pattern: &[I]) -> Vec<usize> {
+    (0..text.len())
+        .filter(|&i| text[i..].starts_with(pattern))
+        .collect()
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use libsais::{SuffixArrayConstruction, common_substrings::CommonSubstring};
+
+mod common;
+
+use common::*;
+
+// the distinct non-empty substrings of the documents with the documents that contain them
+fn naive_document_sets(documents: &[&[u8]]) -> BTreeMap<Vec<u8>, Vec<usize>> {
+    let mut document_sets: BTreeMap<Vec<u8>, Vec<usize>> = BTreeMap::new();
+
+    for (document_index, document) in documents.iter().enumerate() {
+        for start in 0..document.len() {
+            for end in start + 1..=document.len() {
+                let document_set = document_sets
+                    .entry(document[start..end].to_vec())
+                    .or_default();
+
+                if document_set.last() != Some(&document_index) {
+                    document_set.push(document_index);
+                }
+            }
+        }
+    }
+
+    document_sets
+}
+
+fn check_common_substrings(documents: &[&[u8]]) {
+    let text = concatenate_strings(documents.iter().copied());
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let common_substrings = res.longest_common_substrings(&text);
+    assert_eq!(common_substrings.len(), documents.len());
+
+    let document_sets = naive_document_sets(documents);
+
+    for (k, substrings) in (1..=documents.len()).zip(common_substrings) {
+        let longest_len = document_sets
+            .iter()
+            .filter(|(_, document_set)| document_set.len() >= k)
+            .map(|(substring, _)| substring.len())
+            .max();
+
+        let expected: Vec<_> = document_sets
+            .iter()
+            .filter(|(substring, document_set)| {
+                document_set.len() >= k && Some(substring.len()) == longest_len
+            })
+            .map(|(substring, document_set)| (substring.clone(), document_set.clone()))
+            .collect();
+
+        let as_tuple = |common_substring: &CommonSubstring| {
+            let start = res.suffix_array()[common_substring.interval().start()] as usize;
+            (
+                text[start..start + common_substring.len()].to_vec(),
+                common_substring.documents().to_vec(),
+            )
+        };
+
+        let mut actual: Vec<_> = substrings.iter().map(as_tuple).collect();
+        actual.sort_unstable();
+
+        assert_eq!(actual, expected, "k = {k}");
+    }
+}
+
+#[test]
+fn common_substrings_single_document() {
+    check_common_substrings(&[b"abracadabra"]);
+    check_common_substrings(&[b""]);
+}
+
+#[test]
+fn common_substrings() {
+    check_common_substrings(&[b"abcdef", b"xbcdey", b"cdez"]);
+    check_common_substrings(&[b"abcab", b"abcab", b"cab"]);
+    check_common_substrings(&[b"aaaa", b"aa", b"", b"aaa", b"b"]);
+    check_common_substrings(&[
+        b"ACGTACGTTTACGTACGA",
+        b"TTACGTAC",
+        b"GGGTACGTTT",
+        b"CAGTACGA",
+    ]);
+}
+
+#[test]
+fn common_substrings_gsa_example() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+    let documents: Vec<_> = text[..text.len() - 1]
+        .split(|&symbol| symbol == 0)
+        .collect();
+    check_common_substrings(&documents);
+}
+
+#[test]
+#[should_panic]
+fn common_substrings_without_gsa() {
+    let text = b"abcab".as_slice();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .unwrap()
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .unwrap()
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .unwrap();
+
+    res.longest_common_substrings(text);
+}
@@ -0,0 +1,117 @@
+use libsais::{SuffixArrayConstruction, csa::CompressedSuffixArray};
+
+mod common;
+
+use common::*;
+
+#[test]
+fn empty_text_csa() {
+    let res = SuffixArrayConstruction::for_text(&[] as &[u8])
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let csa = CompressedSuffixArray::new(&res, 3);
+
+    assert!(csa.is_empty());
+    assert!(csa.extract(0..0).is_empty());
+    assert_eq!(csa.count(b"a"), 0);
+}
+
+#[test]
+fn csa_access_and_extract() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let suffix_array = res.suffix_array();
+
+    for sampling_rate in [1, 2, 5, 100] {
+        let csa = CompressedSuffixArray::new(&res, sampling_rate);
+
+        for (suffix_array_index, &suffix) in suffix_array.iter().enumerate() {
+            let suffix = suffix as usize;
+
+            assert_eq!(csa.suffix_array_entry(suffix_array_index), suffix);
+            assert_eq!(csa.inverse_suffix_array_entry(suffix), suffix_array_index);
+
+            let expected_psi = suffix_array
+                .iter()
+                .position(|&other| other as usize == suffix + 1);
+            assert_eq!(csa.psi(suffix_array_index), expected_psi);
+        }
+
+        for start in 0..text.len() {
+            for end in start..=text.len() {
+                assert_eq!(csa.extract(start..end), &text[start..end]);
+            }
+        }
+    }
+}
+
+#[test]
+fn csa_search() {
+    let mut text: Vec<i32> = b"mississippi-missouri-mississauga"
+        .iter()
+        .map(|&c| c as i32 * 1000)
+        .collect();
+
+    let res = SuffixArrayConstruction::for_text_mut(&mut text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let csa = CompressedSuffixArray::new(&res, 4);
+    let text = res.text();
+
+    for pattern in [
+        b"".as_slice(),
+        b"ss",
+        b"issi",
+        b"miss",
+        b"missi",
+        b"a",
+        b"sauga",
+        b"saugas",
+        b"x",
+    ] {
+        let pattern: Vec<i32> = pattern.iter().map(|&c| c as i32 * 1000).collect();
+        let expected = naive_occurrences(text, &pattern);
+
+        assert_eq!(csa.count(&pattern), expected.len());
+        assert_eq!(csa.locate_sorted(&pattern), expected);
+    }
+}
+
+#[test]
+fn csa_generalized_suffix_array() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error");
+
+    let csa = CompressedSuffixArray::new(&res, 3);
+
+    assert_eq!(csa.extract(0..text.len()), text);
+
+    for (suffix_array_index, &suffix) in res.suffix_array().iter().enumerate() {
+        assert_eq!(csa.suffix_array_entry(suffix_array_index), suffix as usize);
+    }
+
+    for pattern in [b"ab".as_slice(), b"bac", b"cc", b"abba"] {
+        assert_eq!(
+            csa.locate_sorted(pattern),
+            naive_occurrences(&text, pattern)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+
+use libsais::SuffixArrayConstruction;
+
+mod common;
+
+use common::*;
+
+// the distinct non-empty substrings that don't contain a separator
+fn naive_distinct_substrings(text: &[u8]) -> BTreeSet<&[u8]> {
+    let mut substrings = BTreeSet::new();
+
+    for start in 0..text.len() {
+        for end in start + 1..=text.len() {
+            if text[end - 1] == 0 {
+                break;
+            }
+
+            substrings.insert(&text[start..end]);
+        }
+    }
+
+    substrings
+}
+
+fn check_distinct_substrings(text: &[u8], is_generalized_suffix_array: bool) {
+    let construction = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded();
+
+    let construction = if is_generalized_suffix_array {
+        construction.generalized_suffix_array()
+    } else {
+        construction
+    };
+
+    let res = construction
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let substrings = naive_distinct_substrings(text);
+    assert_eq!(res.num_distinct_substrings(text), substrings.len());
+
+    for max_len in [0, 1, 3, text.len() + 2] {
+        let profile: Vec<_> = (1..=max_len)
+            .map(|len| {
+                substrings
+                    .iter()
+                    .filter(|substring| substring.len() == len)
+                    .count()
+            })
+            .collect();
+
+        assert_eq!(res.distinct_substring_profile(text, max_len), profile);
+    }
+}
+
+#[test]
+fn distinct_substrings() {
+    for text in [
+        b"".as_slice(),
+        b"a",
+        b"aaaaaaa",
+        b"abracadabra",
+        b"mississippi",
+        b"ACGTACGTTTACGTACGA",
+    ] {
+        check_distinct_substrings(text, false);
+    }
+}
+
+#[test]
+fn distinct_substrings_gsa() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+    check_distinct_substrings(&text, true);
+
+    for documents in [
+        [b"abcab".as_slice(), b"abcab", b"cab"].as_slice(),
+        &[b"aaaa", b"", b"aa", b"b"],
+        &[b""],
+    ] {
+        let text = concatenate_strings(documents.iter().copied());
+        check_distinct_substrings(&text, true);
+    }
+}
@@ -0,0 +1,185 @@
+use libsais::{
+    InputElement, OutputElement, SuffixArrayConstruction,
+    esa::{EnhancedSuffixArray, EsaNode},
+    search::SuffixArrayInterval,
+};
+
+mod common;
+
+use common::*;
+
+// checks that the children of every node partition its interval, start with distinct increasing
+// symbols and have the correct depth
+fn check_subtree<I: InputElement, O: OutputElement>(
+    esa: &EnhancedSuffixArray<'_, I, O>,
+    node: EsaNode,
+) -> usize {
+    let text = esa.text();
+    let suffix = |i: usize| &text[esa.suffix_array()[i].to_usize().unwrap()..];
+
+    if node.is_leaf() {
+        assert_eq!(node.depth(), suffix(node.interval().start()).len());
+        return 1;
+    }
+
+    let interval = node.interval();
+    let expected_depth = interval
+        .range()
+        .skip(1)
+        .map(|i| esa.lcp()[i].to_usize().unwrap())
+        .min()
+        .unwrap();
+    assert_eq!(node.depth(), expected_depth);
+
+    let mut num_leaves = 0;
+    let mut expected_start = interval.start();
+    let mut previous_symbol = None;
+
+    for child in esa.children(node) {
+        assert_eq!(child.interval().start(), expected_start);
+        assert!(child.depth() > node.depth() || child.is_leaf());
+
+        let symbol = suffix(child.interval().start()).get(node.depth()).copied();
+        if let Some(symbol) = symbol.filter(|&symbol| symbol != I::zero()) {
+            assert!(previous_symbol < Some(symbol));
+            assert_eq!(esa.child(node, symbol), Some(child));
+        }
+        previous_symbol = symbol;
+
+        expected_start = child.interval().end();
+        num_leaves += check_subtree(esa, child);
+    }
+
+    assert_eq!(expected_start, interval.end());
+    assert_eq!(num_leaves, interval.len());
+
+    num_leaves
+}
+
+#[test]
+fn empty_text_esa() {
+    let res = SuffixArrayConstruction::for_text(&[] as &[u8])
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let esa = EnhancedSuffixArray::new(&res, &[]);
+
+    assert_eq!(esa.root().interval(), SuffixArrayInterval::new(0, 0));
+    assert_eq!(esa.children(esa.root()).count(), 0);
+    assert_eq!(esa.count(b"a"), 0);
+}
+
+#[test]
+fn esa_single_symbol_text() {
+    let text = b"aaaa";
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let esa = EnhancedSuffixArray::new(&res, text);
+    let root = esa.root();
+
+    assert_eq!(root.depth(), 1);
+    let child = esa.child(root, b'a').unwrap();
+    assert_eq!(child.depth(), 2);
+    assert_eq!(child.interval(), SuffixArrayInterval::new(1, 4));
+
+    assert_eq!(check_subtree(&esa, root), 4);
+    assert_eq!(esa.locate_sorted(b"aa"), vec![0, 1, 2]);
+}
+
+#[test]
+fn esa_traversal_and_search() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let esa = EnhancedSuffixArray::new(&res, text);
+
+    assert_eq!(check_subtree(&esa, esa.root()), text.len());
+
+    for pattern in [
+        b"".as_slice(),
+        b"a",
+        b"ab",
+        b"aba",
+        b"abba",
+        b"abababcabba",
+        b"abababcabbaa",
+        b"c",
+        b"ca",
+        b"bb",
+        b"d",
+    ] {
+        let expected = naive_occurrences(text, pattern);
+
+        assert_eq!(esa.count(pattern), expected.len());
+        assert_eq!(esa.locate_sorted(pattern), expected);
+    }
+}
+
+#[test]
+fn esa_generalized_suffix_array_and_u16_text() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+    let text: Vec<u16> = text.into_iter().map(|c| c as u16 * 250).collect();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let esa = EnhancedSuffixArray::new(&res, &text);
+
+    assert_eq!(check_subtree(&esa, esa.root()), text.len());
+
+    for pattern in [b"ab".as_slice(), b"bac", b"cc", b"abba", b"cab"] {
+        let pattern: Vec<u16> = pattern.iter().map(|&c| c as u16 * 250).collect();
+        assert_eq!(
+            esa.locate_sorted(&pattern),
+            naive_occurrences(&text, &pattern)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use libsais::{
+    BwtConstruction, SuffixArrayConstruction, bwt::AuxIndicesSamplingRate, fm_index::FmIndex,
+    search::SuffixArrayInterval,
+};
+
+mod common;
+
+use common::*;
+
+#[test]
+fn empty_text_fm_index() {
+    let bwt = BwtConstruction::for_text(&[] as &[u8])
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = FmIndex::new(&bwt, 4);
+
+    assert!(index.is_empty());
+    assert_eq!(index.interval(b""), SuffixArrayInterval::new(0, 0));
+    assert_eq!(index.count(b"a"), 0);
+    assert!(index.locate(b"a").is_empty());
+}
+
+#[test]
+fn fm_index_count_and_locate() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let suffix_array_result = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    for sampling_rate in [1, 2, 3, 64] {
+        let index = FmIndex::new(&bwt, sampling_rate);
+
+        for pattern in [
+            b"".as_slice(),
+            b"a",
+            b"ab",
+            b"aba",
+            b"abba",
+            b"abababcabba",
+            b"abababcabbaa",
+            b"c",
+            b"ca",
+            b"bb",
+            b"d",
+            b"0",
+        ] {
+            let expected = naive_occurrences(text, pattern);
+
+            assert_eq!(index.count(pattern), expected.len());
+            assert_eq!(index.locate_sorted(pattern), expected);
+
+            if !expected.is_empty() {
+                assert_eq!(
+                    index.interval(pattern),
+                    suffix_array_result.interval(pattern)
+                );
+            }
+        }
+
+        for (suffix_array_index, &suffix) in suffix_array_result.suffix_array().iter().enumerate() {
+            assert_eq!(
+                index.suffix_array_entry(suffix_array_index),
+                suffix as usize
+            );
+        }
+    }
+}
+
+#[test]
+fn fm_index_from_aux_indices() {
+    let text = b"mississippi is a state on the mississippi river";
+
+    let bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer64()
+        .single_threaded()
+        .with_aux_indices(AuxIndicesSamplingRate::from(4))
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = FmIndex::from_aux_indices(&bwt);
+
+    assert_eq!(index.sampling_rate(), 4);
+    assert_eq!(index.primary_index(), bwt.aux_indices()[0] as usize);
+
+    for pattern in [
+        b"mississippi".as_slice(),
+        b"ssi",
+        b"i",
+        b" ",
+        b"river",
+        b"rivers",
+        b"x",
+    ] {
+        assert_eq!(
+            index.locate_sorted(pattern),
+            naive_occurrences(text, pattern)
+        );
+    }
+}
+
+#[test]
+fn fm_index_u16_text() {
+    let text: Vec<u16> = b"abracadabra-abracadabra"
+        .iter()
+        .map(|&c| c as u16 * 300)
+        .collect();
+
+    let bwt = BwtConstruction::for_text(&text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = FmIndex::new(&bwt, 5);
+
+    for pattern in [b"abra".as_slice(), b"cad", b"a-a", b"bra-", b"rab"] {
+        let pattern: Vec<u16> = pattern.iter().map(|&c| c as u16 * 300).collect();
+        let expected = naive_occurrences(&text, &pattern);
+
+        assert_eq!(index.count(&pattern), expected.len());
+        assert_eq!(index.locate_sorted(&pattern), expected);
+    }
+}
+
+#[test]
+fn fm_index_large_u16_alphabet() {
+    // more distinct symbols than the occurrence table supports
+    let text: Vec<u16> = (0..3000u32)
+        .map(|i| ((i * i * 7 + i / 3) % 1021) as u16 + 1)
+        .collect();
+
+    let bwt = BwtConstruction::for_text(&text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = FmIndex::new(&bwt, 8);
+
+    for start in (0..text.len() - 3).step_by(97) {
+        for len in [1, 2, 3] {
+            let pattern = &text[start..start + len];
+            assert_eq!(
+                index.locate_sorted(pattern),
+                naive_occurrences(&text, pattern)
+            );
+        }
+    }
+
+    assert_eq!(index.count(&[1022]), 0);
+}
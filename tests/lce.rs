@@ -0,0 +1,112 @@
+use libsais::{
+    SuffixArrayConstruction,
+    lce::{
+        LceIndex, RangeMinimumQuery, inverse_suffix_array, next_smaller_values,
+        previous_smaller_values,
+    },
+};
+
+mod common;
+
+use common::*;
+
+fn naive_lce(text: &[u8], i: usize, j: usize) -> usize {
+    text[i..]
+        .iter()
+        .zip(&text[j..])
+        .take_while(|&(a, b)| a == b && *a != 0)
+        .count()
+}
+
+#[test]
+fn range_minimum_query_multiple_blocks() {
+    // pseudo random values with many ties, spanning several blocks
+    let values: Vec<i64> = (0..700i64).map(|i| (i * 7919 + 13) % 97 / 3).collect();
+    let rmq = RangeMinimumQuery::new(&values);
+
+    for start in (0..values.len()).step_by(7) {
+        for end in (start + 1..=values.len()).step_by(5) {
+            let expected = (start..end).min_by_key(|&i| (values[i], i)).unwrap();
+
+            assert_eq!(rmq.query(start, end), expected);
+            assert_eq!(rmq.min(start, end), values[expected]);
+        }
+    }
+
+    let empty: [i32; 0] = [];
+    assert_eq!(RangeMinimumQuery::new(&empty).values().len(), 0);
+}
+
+#[test]
+fn lce_queries() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let index = LceIndex::new(&res);
+
+    for i in 0..text.len() {
+        assert_eq!(
+            index.inverse_suffix_array()[res.suffix_array()[i] as usize],
+            i as i32
+        );
+
+        for j in 0..text.len() {
+            assert_eq!(index.lce(i, j), naive_lce(text, i, j));
+        }
+    }
+}
+
+#[test]
+fn lce_generalized_suffix_array() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let index = LceIndex::new(&res);
+
+    for i in 0..text.len() {
+        for j in (0..text.len()).filter(|&j| j != i) {
+            assert_eq!(index.lce(i, j), naive_lce(&text, i, j));
+        }
+    }
+}
+
+#[test]
+fn smaller_values_and_inverse_suffix_array() {
+    let values = [3i32, 1, 4, 1, 5, 9, 2, 6];
+
+    assert_eq!(
+        previous_smaller_values(&values),
+        vec![-1, -1, 1, -1, 3, 4, 3, 6]
+    );
+    assert_eq!(next_smaller_values(&values), vec![1, 8, 3, 8, 6, 6, 8, 8]);
+
+    assert_eq!(inverse_suffix_array(&[2i32, 0, 1]), vec![1, 2, 0]);
+    assert!(previous_smaller_values::<i64>(&[]).is_empty());
+}
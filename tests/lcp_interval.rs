@@ -0,0 +1,198 @@
+use libsais::{OutputElement, SuffixArrayConstruction, search::SuffixArrayInterval};
+
+mod common;
+
+use common::*;
+
+// all (lcp, interval) pairs, computed by checking every interval of length at least 2
+fn naive_lcp_intervals<O: OutputElement>(lcp: &[O]) -> Vec<(usize, SuffixArrayInterval)> {
+    let lcp: Vec<isize> = lcp.iter().map(|&l| l.to_isize().unwrap()).collect();
+    let lcp_value = |i: usize| if i == lcp.len() { -1 } else { lcp[i] };
+    let mut intervals = Vec::new();
+
+    for start in 0..lcp.len() {
+        for end in start + 2..=lcp.len() {
+            let interval_lcp = (start + 1..end).map(|i| lcp[i]).min().unwrap();
+            let left_is_maximal = start == 0 || lcp_value(start) < interval_lcp;
+
+            if left_is_maximal && lcp_value(end) < interval_lcp {
+                intervals.push((interval_lcp as usize, SuffixArrayInterval::new(start, end)));
+            }
+        }
+    }
+
+    intervals.sort_unstable();
+    intervals
+}
+
+#[test]
+fn empty_and_single_symbol_text_lcp_intervals() {
+    let res = SuffixArrayConstruction::for_text(b"".as_slice())
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    assert_eq!(res.lcp_intervals().count(), 0);
+    assert_eq!(res.fold_lcp_intervals(|_| 1, |_, _| 0), None);
+
+    let res = SuffixArrayConstruction::for_text(b"aaa".as_slice())
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let intervals: Vec<_> = res
+        .lcp_intervals()
+        .map(|interval| (interval.lcp(), interval.interval()))
+        .collect();
+
+    assert_eq!(
+        intervals,
+        vec![
+            (2, SuffixArrayInterval::new(1, 3)),
+            (1, SuffixArrayInterval::new(0, 3))
+        ]
+    );
+}
+
+#[test]
+fn lcp_intervals_postorder_with_children() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let intervals: Vec<_> = res.lcp_intervals().collect();
+
+    let mut actual: Vec<_> = intervals
+        .iter()
+        .map(|interval| (interval.lcp(), interval.interval()))
+        .collect();
+    actual.sort_unstable();
+    assert_eq!(actual, naive_lcp_intervals(res.lcp()));
+
+    assert_eq!(
+        intervals.last().unwrap().interval(),
+        SuffixArrayInterval::new(0, text.len())
+    );
+
+    for (i, interval) in intervals.iter().enumerate() {
+        let children = interval.child_intervals();
+
+        assert!(children.len() >= 2);
+        assert_eq!(children[0].start(), interval.interval().start());
+        assert_eq!(children.last().unwrap().end(), interval.interval().end());
+        assert!(children.windows(2).all(|w| w[0].end() == w[1].start()));
+
+        // postorder: internal children are reported before their parent
+        for child in children.iter().filter(|child| child.len() > 1) {
+            assert!(
+                intervals[..i]
+                    .iter()
+                    .any(|other| other.interval() == *child)
+            );
+        }
+    }
+}
+
+#[test]
+fn fold_lcp_intervals_smallest_position() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let suffix_array = res.suffix_array();
+    let mut visited = Vec::new();
+
+    let root_min = res.fold_lcp_intervals(
+        |suffix_array_index| suffix_array[suffix_array_index] as usize,
+        |interval, children| {
+            let min = children.into_iter().min().unwrap();
+
+            let expected_min = interval
+                .interval()
+                .range()
+                .map(|i| suffix_array[i] as usize)
+                .min()
+                .unwrap();
+            assert_eq!(min, expected_min);
+
+            visited.push(interval.interval());
+            min
+        },
+    );
+
+    assert_eq!(root_min, Some(0));
+    assert_eq!(visited.len(), naive_lcp_intervals(res.lcp()).len());
+}
+
+#[test]
+fn lcp_intervals_generalized_suffix_array() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let mut actual: Vec<_> = res
+        .lcp_intervals()
+        .map(|interval| (interval.lcp(), interval.interval()))
+        .collect();
+    actual.sort_unstable();
+
+    assert_eq!(actual, naive_lcp_intervals(res.lcp()));
+
+    let num_leaves = res.fold_lcp_intervals(|_| 1, |_, children| children.into_iter().sum());
+    assert_eq!(num_leaves, Some(text.len()));
+}
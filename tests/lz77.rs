@@ -0,0 +1,103 @@
+use libsais::{
+    SuffixArrayConstruction,
+    lz77::{self, Phrase},
+};
+
+mod common;
+
+use common::*;
+
+fn naive_lpf_array(text: &[u8]) -> Vec<usize> {
+    (0..text.len())
+        .map(|position| {
+            (0..position)
+                .map(|source| {
+                    text[source..]
+                        .iter()
+                        .zip(&text[position..])
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                })
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+fn check_lz77(text: &[u8]) {
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error");
+
+    let lpf = naive_lpf_array(text);
+    assert_eq!(res.lpf_array(text), lpf);
+
+    let parse = res.lz77_parse(text);
+    let mut position = 0;
+
+    for phrase in &parse {
+        match *phrase {
+            Phrase::Literal(symbol) => {
+                assert_eq!(lpf[position], 0);
+                assert_eq!(symbol, text[position]);
+            }
+            Phrase::Copy { source, len } => {
+                assert_eq!(len, lpf[position]);
+                assert!(source < position);
+                assert_eq!(text[source..source + len], text[position..position + len]);
+            }
+        }
+
+        position += phrase.len();
+    }
+
+    assert_eq!(position, text.len());
+    assert_eq!(lz77::decode(&parse), text);
+}
+
+#[test]
+fn lz77() {
+    for text in [
+        b"".as_slice(),
+        b"a",
+        b"aaaaaaa",
+        b"abababb",
+        b"abracadabra",
+        b"mississippi",
+        b"ACGTACGTTTACGTACGA",
+        b"abcdefg",
+    ] {
+        check_lz77(text);
+    }
+}
+
+#[test]
+fn lz77_basic_example() {
+    let (text, _, _, _) = setup_basic_example();
+    check_lz77(text);
+}
+
+#[test]
+#[should_panic]
+fn lz77_gsa() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .unwrap()
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .unwrap();
+
+    res.lz77_parse(&text);
+}
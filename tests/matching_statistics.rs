@@ -0,0 +1,77 @@
+use libsais::{SuffixArrayConstruction, suffix_automaton::SuffixAutomaton};
+
+mod common;
+
+use common::*;
+
+fn check_matching_statistics(text: &[u8], queries: &[&[u8]]) {
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let automaton = SuffixAutomaton::new(&res, text);
+
+    for query in queries {
+        let matching_statistics = automaton.matching_statistics(query);
+        assert_eq!(matching_statistics.len(), query.len());
+
+        for (query_start, statistic) in matching_statistics.iter().enumerate() {
+            let expected_len = (query_start + 1..=query.len())
+                .rev()
+                .find(|&end| !naive_occurrences(text, &query[query_start..end]).is_empty())
+                .map_or(0, |end| end - query_start);
+
+            assert_eq!(statistic.len(), expected_len);
+
+            if statistic.is_empty() {
+                assert_eq!(statistic.text_position(), None);
+                continue;
+            }
+
+            let matched = &query[query_start..query_start + expected_len];
+            let occurrences = naive_occurrences(text, matched);
+            let mut positions: Vec<_> = res.suffix_array()[statistic.interval().range()]
+                .iter()
+                .map(|&suffix| suffix as usize)
+                .collect();
+            positions.sort_unstable();
+
+            assert_eq!(positions, occurrences);
+            assert!(occurrences.contains(&statistic.text_position().unwrap()));
+        }
+    }
+}
+
+#[test]
+fn matching_statistics_empty_text_and_query() {
+    check_matching_statistics(b"", &[b"", b"abc"]);
+    check_matching_statistics(b"abc", &[b""]);
+}
+
+#[test]
+fn matching_statistics() {
+    check_matching_statistics(
+        b"the quick brown fox jumps over the lazy dog",
+        &[
+            b"a quick brown cat",
+            b"lazy fox jumps",
+            b"xyz the",
+            b"ooooo",
+        ],
+    );
+    check_matching_statistics(
+        b"ACGTTGCAACGTACGTTTGACA",
+        &[b"ACGTACGTTTG", b"TTTTTTT", b"NACGN", b"CAACGTACGTTTGACAT"],
+    );
+    check_matching_statistics(b"aaaaaaaa", &[b"aaaaaaaaaaaa", b"abaaab"]);
+}
@@ -0,0 +1,100 @@
+use libsais::mum::{self, MaximalMatch};
+
+// (position_a, position_b, len) of all maximal exact matches, computed by extending every pair of positions
+fn naive_maximal_exact_matches(a: &[u8], b: &[u8]) -> Vec<(usize, usize, usize)> {
+    let mut matches = Vec::new();
+
+    for position_a in 0..a.len() {
+        for position_b in 0..b.len() {
+            let is_left_maximal =
+                position_a == 0 || position_b == 0 || a[position_a - 1] != b[position_b - 1];
+
+            let len = a[position_a..]
+                .iter()
+                .zip(&b[position_b..])
+                .take_while(|(x, y)| x == y)
+                .count();
+
+            if is_left_maximal && len > 0 {
+                matches.push((position_a, position_b, len));
+            }
+        }
+    }
+
+    matches
+}
+
+fn count(text: &[u8], pattern: &[u8]) -> usize {
+    text.windows(pattern.len())
+        .filter(|&window| window == pattern)
+        .count()
+}
+
+fn as_tuples(matches: Vec<MaximalMatch>) -> Vec<(usize, usize, usize)> {
+    matches
+        .into_iter()
+        .map(|m| (m.position_a(), m.position_b(), m.len()))
+        .collect()
+}
+
+fn check_maximal_matches(a: &[u8], b: &[u8]) {
+    let naive_mems = naive_maximal_exact_matches(a, b);
+
+    for min_len in [1, 3] {
+        let expected_mems: Vec<_> = naive_mems
+            .iter()
+            .copied()
+            .filter(|&(_, _, len)| len >= min_len)
+            .collect();
+
+        let expected_mums: Vec<_> = expected_mems
+            .iter()
+            .copied()
+            .filter(|&(position_a, _, len)| {
+                let substring = &a[position_a..position_a + len];
+                count(a, substring) == 1 && count(b, substring) == 1
+            })
+            .collect();
+
+        let mems = mum::maximal_exact_matches(a, b, min_len).unwrap();
+        assert_eq!(as_tuples(mems), expected_mems);
+
+        let mums = mum::maximal_unique_matches(a, b, min_len).unwrap();
+        assert_eq!(as_tuples(mums), expected_mums);
+    }
+}
+
+#[test]
+fn maximal_matches_empty_texts() {
+    check_maximal_matches(b"", b"");
+    check_maximal_matches(b"abc", b"".as_slice());
+    check_maximal_matches(b"abc", b"xyz");
+}
+
+#[test]
+fn maximal_matches() {
+    check_maximal_matches(b"GATTACAGATTACA", b"TTACAGGATTAC");
+    check_maximal_matches(b"abracadabra", b"cadabracad");
+    check_maximal_matches(b"aaaaa", b"aaa");
+    check_maximal_matches(b"ACGTTGCAACGTACGTTTGACAGGT", b"TTGCAACGAACGTACGTTAGACAGG");
+}
+
+#[test]
+fn maximal_exact_matches_repetitive() {
+    let text = vec![b'A'; 2000];
+    let mems = mum::maximal_exact_matches(&text, &text, 1).unwrap();
+
+    // every MEM starts at the start of one of the texts
+    assert_eq!(mems.len(), 2 * text.len() - 1);
+    assert!(
+        mems.iter()
+            .all(|m| (m.position_a() == 0 || m.position_b() == 0)
+                && m.len() == text.len() - m.position_a().max(m.position_b()))
+    );
+}
+
+#[test]
+#[should_panic]
+fn maximal_matches_text_with_zero() {
+    let _ = mum::maximal_unique_matches(b"ab\0c".as_slice(), b"abc", 1);
+}
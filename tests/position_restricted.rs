@@ -0,0 +1,84 @@
+use libsais::{SuffixArrayConstruction, position_restricted::PositionRestrictedIndex};
+
+mod common;
+
+use common::*;
+
+#[test]
+fn empty_text_position_restricted() {
+    let res = SuffixArrayConstruction::for_text(&[] as &[u8])
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = PositionRestrictedIndex::new(&res);
+
+    assert!(index.is_empty());
+    assert_eq!(index.count(b"", 0..10), 0);
+    assert!(index.locate(b"a", 0..10).is_empty());
+}
+
+#[test]
+fn position_restricted_count_and_locate() {
+    let text: Vec<u8> = (0..400u32)
+        .map(|i| b"acgt"[((i * 7 + i / 13) % 4) as usize])
+        .collect();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = PositionRestrictedIndex::new(&res);
+
+    for pattern in [b"".as_slice(), b"a", b"ac", b"gta", b"cgtc", b"tttt"] {
+        let occurrences = naive_occurrences(&text, pattern);
+
+        for (start, end) in [
+            (0, 400),
+            (0, 0),
+            (17, 18),
+            (50, 250),
+            (399, 1000),
+            (123, 124),
+        ] {
+            let expected: Vec<_> = occurrences
+                .iter()
+                .copied()
+                .filter(|&position| position >= start && position < end)
+                .collect();
+
+            assert_eq!(index.count(pattern, start..end), expected.len());
+            assert_eq!(index.locate(pattern, start..end), expected);
+        }
+    }
+}
+
+#[test]
+fn position_restricted_generalized_suffix_array() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = PositionRestrictedIndex::new(&res);
+    let second_text_start = text.iter().position(|&c| c == 0).unwrap() + 1;
+
+    for pattern in [b"ab".as_slice(), b"ba", b"c"] {
+        let expected: Vec<_> = naive_occurrences(&text, pattern)
+            .into_iter()
+            .filter(|&position| position >= second_text_start)
+            .collect();
+
+        assert_eq!(
+            index.locate(pattern, second_text_start..text.len()),
+            expected
+        );
+    }
+}
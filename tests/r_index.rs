@@ -0,0 +1,143 @@
+use libsais::{
+    BwtConstruction, SuffixArrayConstruction,
+    bwt::AuxIndicesSamplingRate,
+    r_index::{BwtRun, RIndex, RunLengthBwt},
+};
+
+mod common;
+
+use common::*;
+
+#[test]
+fn empty_text_r_index() {
+    let bwt = BwtConstruction::for_text(&[] as &[u8])
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let run_length_bwt = RunLengthBwt::new(&bwt);
+    assert_eq!(run_length_bwt.num_runs(), 1);
+
+    let index = RIndex::new(run_length_bwt);
+
+    assert!(index.is_empty());
+    assert_eq!(index.count(b""), 0);
+    assert!(index.locate(b"").is_empty());
+    assert!(index.locate(b"a").is_empty());
+}
+
+#[test]
+fn run_length_bwt_runs() {
+    let text = b"banana";
+
+    let bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    // full BWT with sentinel: annb$aa
+    let run_length_bwt = RunLengthBwt::new(&bwt);
+
+    assert_eq!(run_length_bwt.num_runs(), 5);
+    assert_eq!(run_length_bwt.run_starts(), &[0, 1, 3, 4, 5]);
+    assert_eq!(
+        run_length_bwt.runs().nth(3),
+        Some(BwtRun {
+            start: 4,
+            len: 1,
+            symbol: None
+        })
+    );
+    assert_eq!(run_length_bwt.access(2), Some(b'n'));
+    assert_eq!(run_length_bwt.rank(b'a', 7), 3);
+    assert_eq!(run_length_bwt.rank(b'n', 2), 1);
+    assert_eq!(run_length_bwt.to_bwt(), bwt.bwt());
+}
+
+#[test]
+fn r_index_count_and_locate() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let suffix_array_result = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = RIndex::new(RunLengthBwt::new(&bwt));
+
+    for pattern in [
+        b"".as_slice(),
+        b"a",
+        b"ab",
+        b"aba",
+        b"abba",
+        b"abababcabba",
+        b"abababcabbaa",
+        b"c",
+        b"ca",
+        b"bb",
+        b"d",
+        b"0",
+    ] {
+        let expected = naive_occurrences(text, pattern);
+
+        assert_eq!(index.count(pattern), expected.len());
+        assert_eq!(index.locate_sorted(pattern), expected);
+
+        if !expected.is_empty() {
+            let interval = suffix_array_result.interval(pattern);
+            assert_eq!(index.interval(pattern), interval);
+            assert_eq!(
+                index.locate(pattern),
+                suffix_array_result.locate(pattern).collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+#[test]
+fn r_index_repetitive_text_from_aux_indices() {
+    let text: Vec<u16> = b"ACGTTGCA"
+        .repeat(20)
+        .into_iter()
+        .chain(b"ACGATGCA".repeat(5))
+        .map(|c| c as u16 * 200)
+        .collect();
+
+    let bwt = BwtConstruction::for_text(&text)
+        .with_owned_temporary_array_buffer64()
+        .single_threaded()
+        .with_aux_indices(AuxIndicesSamplingRate::from(8))
+        .run()
+        .expect("libsais should run without an error");
+
+    let run_length_bwt = RunLengthBwt::from_aux_indices(&bwt);
+    assert!(run_length_bwt.num_runs() < text.len() / 4);
+    assert_eq!(run_length_bwt.to_bwt(), bwt.bwt());
+
+    let index = RIndex::new(run_length_bwt);
+
+    for pattern in [
+        b"A".as_slice(),
+        b"ACGT",
+        b"GCAACGA",
+        b"TGCAACGTTG",
+        b"ACGATGCAACGATGCA",
+        b"AAAA",
+    ] {
+        let pattern: Vec<u16> = pattern.iter().map(|&c| c as u16 * 200).collect();
+        let expected = naive_occurrences(&text, &pattern);
+
+        assert_eq!(index.count(&pattern), expected.len());
+        assert_eq!(index.locate_sorted(&pattern), expected);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use libsais::{SuffixArrayConstruction, repeats::Repeat};
+
+mod common;
+
+use common::*;
+
+// the distinct substrings without separators that occur at least twice, with their occurrences
+fn naive_repeats(text: &[u8]) -> BTreeMap<&[u8], Vec<usize>> {
+    let mut repeats = BTreeMap::new();
+
+    for start in 0..text.len() {
+        for end in start + 1..=text.len() {
+            let substring = &text[start..end];
+
+            if substring.contains(&0) {
+                break;
+            }
+
+            let occurrences = naive_occurrences(text, substring);
+            if occurrences.len() > 1 {
+                repeats.insert(substring, occurrences);
+            }
+        }
+    }
+
+    repeats
+}
+
+// the start and end of the text and the separators are unique contexts
+fn is_naive_maximal(text: &[u8], len: usize, occurrences: &[usize]) -> bool {
+    let context = |position: Option<usize>| {
+        position
+            .and_then(|position| text.get(position))
+            .filter(|&&symbol| symbol != 0)
+    };
+
+    let left_contexts: Vec<_> = occurrences
+        .iter()
+        .map(|&position| context(position.checked_sub(1)))
+        .collect();
+    let right_contexts: Vec<_> = occurrences
+        .iter()
+        .map(|&position| context(Some(position + len)))
+        .collect();
+
+    let is_maximal = |contexts: &[Option<&u8>]| {
+        contexts.contains(&None) || contexts.iter().any(|&context| context != contexts[0])
+    };
+
+    is_maximal(&left_contexts) && is_maximal(&right_contexts)
+}
+
+fn check_repeats(text: &[u8], is_generalized_suffix_array: bool) {
+    let construction = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded();
+
+    let construction = if is_generalized_suffix_array {
+        construction.generalized_suffix_array()
+    } else {
+        construction
+    };
+
+    let res = construction
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let repeats = naive_repeats(text);
+    let as_tuples = |repeats: Vec<Repeat>| -> Vec<(Vec<u8>, Vec<usize>)> {
+        let mut tuples: Vec<_> = repeats
+            .into_iter()
+            .map(|repeat| {
+                assert!(repeat.num_occurrences() > 1);
+                let start = repeat.positions()[0];
+                (
+                    text[start..start + repeat.len()].to_vec(),
+                    repeat.positions().to_vec(),
+                )
+            })
+            .collect();
+        tuples.sort_unstable();
+        tuples
+    };
+
+    let maximal_repeats: Vec<_> = repeats
+        .iter()
+        .filter(|(substring, occurrences)| is_naive_maximal(text, substring.len(), occurrences))
+        .map(|(substring, occurrences)| (substring.to_vec(), occurrences.clone()))
+        .collect();
+
+    assert_eq!(as_tuples(res.maximal_repeats(text, 1)), maximal_repeats);
+
+    let long_maximal_repeats: Vec<_> = maximal_repeats
+        .iter()
+        .filter(|(substring, _)| substring.len() >= 3)
+        .cloned()
+        .collect();
+    assert_eq!(
+        as_tuples(res.maximal_repeats(text, 3)),
+        long_maximal_repeats
+    );
+
+    let supermaximal_repeats: Vec<_> = maximal_repeats
+        .iter()
+        .filter(|(substring, _)| {
+            !maximal_repeats.iter().any(|(other, _)| {
+                other.len() > substring.len()
+                    && other
+                        .windows(substring.len())
+                        .any(|window| window == substring)
+            })
+        })
+        .cloned()
+        .collect();
+
+    assert_eq!(
+        as_tuples(res.supermaximal_repeats(text, 1)),
+        supermaximal_repeats
+    );
+
+    let longest_len = repeats.keys().map(|substring| substring.len()).max();
+    let longest = res.longest_repeated_substring();
+    assert_eq!(longest.as_ref().map(|repeat| repeat.len()), longest_len);
+
+    if let Some(longest) = longest {
+        let start = longest.positions()[0];
+        assert_eq!(
+            repeats[&text[start..start + longest.len()]],
+            longest.positions()
+        );
+    }
+
+    let non_overlapping_len = repeats
+        .iter()
+        .filter(|(substring, occurrences)| {
+            occurrences.last().unwrap() - occurrences[0] >= substring.len()
+        })
+        .map(|(substring, _)| substring.len())
+        .max();
+    let non_overlapping = res.longest_non_overlapping_repeated_substring();
+    assert_eq!(
+        non_overlapping.as_ref().map(|repeat| repeat.len()),
+        non_overlapping_len
+    );
+
+    if let Some(non_overlapping) = non_overlapping {
+        let start = non_overlapping.positions()[0];
+        assert_eq!(
+            repeats[&text[start..start + non_overlapping.len()]],
+            non_overlapping.positions()
+        );
+    }
+}
+
+#[test]
+fn repeats_without_repeats() {
+    for text in [b"".as_slice(), b"a", b"abcdef"] {
+        check_repeats(text, false);
+    }
+}
+
+#[test]
+fn repeats() {
+    for text in [
+        b"abracadabra".as_slice(),
+        b"aa",
+        b"aaaaaaa",
+        b"abababcabba",
+        b"mississippi",
+        b"ACGTACGTTTACGTACGA",
+    ] {
+        check_repeats(text, false);
+    }
+}
+
+#[test]
+fn repeats_gsa() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+    check_repeats(&text, true);
+
+    let text = concatenate_strings([b"abcab".as_slice(), b"abcab", b"cab"]);
+    check_repeats(&text, true);
+}
@@ -0,0 +1,243 @@
+use libsais::{
+    SuffixArrayConstruction, ThreadCount,
+    search::{LcpLrIndex, SuffixArrayInterval},
+};
+
+mod common;
+
+use common::*;
+
+#[test]
+fn empty_text_search() {
+    let res = SuffixArrayConstruction::for_text(&[] as &[u8])
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    assert_eq!(res.interval(b""), SuffixArrayInterval::new(0, 0));
+    assert_eq!(res.count(b"a"), 0);
+    assert!(res.locate(b"a").next().is_none());
+}
+
+#[test]
+fn count_and_locate() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    for pattern in [
+        b"".as_slice(),
+        b"a",
+        b"ab",
+        b"aba",
+        b"abba",
+        b"abababcabba",
+        b"abababcabbaa",
+        b"c",
+        b"ca",
+        b"bb",
+        b"d",
+        b"0",
+    ] {
+        let expected = naive_occurrences(text, pattern);
+
+        assert_eq!(res.count(pattern), expected.len());
+        assert_eq!(res.locate_sorted(pattern), expected);
+        assert_eq!(res.locate(pattern).len(), expected.len());
+    }
+}
+
+#[test]
+fn search_with_64_bit_output_and_u16_input() {
+    let text: Vec<u16> = b"mississippi".iter().map(|&c| c as u16 * 300).collect();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    for start in 0..text.len() {
+        for end in start..=text.len() {
+            let pattern = &text[start..end];
+            assert_eq!(
+                res.locate_sorted(pattern),
+                naive_occurrences(&text, pattern)
+            );
+        }
+    }
+}
+
+#[test]
+fn refine_interval_incrementally() {
+    let text = b"barnabasbabblesaboutbananas";
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let pattern = b"banana";
+    let mut interval = res.interval(b"");
+
+    for (i, &symbol) in pattern.iter().enumerate() {
+        interval = res.refine_interval(interval, i, symbol);
+        assert_eq!(interval, res.interval(&pattern[..=i]));
+    }
+
+    assert_eq!(interval.len(), 1);
+    assert_eq!(res.locate(pattern).collect::<Vec<_>>(), vec![20]);
+}
+
+#[test]
+fn search_generalized_suffix_array_with_lcp() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    for pattern in [
+        b"a".as_slice(),
+        b"ba",
+        b"bac",
+        b"abba",
+        b"cc",
+        b"a\0",
+        b"abba\0b",
+    ] {
+        let expected = naive_occurrences(&text, pattern);
+
+        assert_eq!(res.count(&text, pattern), expected.len());
+        assert_eq!(res.locate_sorted(&text, pattern), expected);
+    }
+}
+
+#[test]
+fn lcp_lr_index_agrees_with_binary_search() {
+    let text = b"barnabasbabblesaboutbananasbanana";
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let index = LcpLrIndex::new(&res, text);
+
+    for start in 0..text.len() {
+        for end in start..=text.len() {
+            let pattern = &text[start..end];
+            assert_eq!(index.interval(pattern), res.interval(text, pattern));
+        }
+    }
+
+    for pattern in [b"bananasx".as_slice(), b"x", b"aa", b"bab", b"s"] {
+        assert_eq!(
+            index.locate_sorted(pattern),
+            naive_occurrences(text, pattern)
+        );
+    }
+}
+
+#[test]
+fn lcp_lr_index_64_bit_generalized_suffix_array() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    let res = SuffixArrayConstruction::for_text(&text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .generalized_suffix_array()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let index = LcpLrIndex::new(&res, &text);
+
+    for start in 0..text.len() {
+        for end in start..=text.len() {
+            let pattern = &text[start..end];
+
+            if pattern.contains(&0) {
+                continue;
+            }
+
+            assert_eq!(
+                index.locate_sorted(pattern),
+                naive_occurrences(&text, pattern)
+            );
+        }
+    }
+}
+
+#[test]
+fn batch_search() {
+    let text = b"mississippi-missouri-mississauga-misty".as_slice();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let patterns: Vec<&[u8]> = vec![
+        b"miss", b"mis", b"", b"missi", b"s", b"ss", b"issi", b"miss", b"x", b"mistyx", b"sauga",
+        b"m", b"i", b"ippi-m", b"-",
+    ];
+
+    for thread_count in [
+        ThreadCount::fixed(1),
+        ThreadCount::fixed(3),
+        ThreadCount::fixed(100),
+        ThreadCount::openmp_default(),
+    ] {
+        let intervals = res.batch_intervals(&patterns, thread_count);
+
+        assert_eq!(intervals.len(), patterns.len());
+
+        for (pattern, interval) in patterns.iter().zip(intervals) {
+            assert_eq!(interval.len(), naive_occurrences(text, pattern).len());
+
+            if !interval.is_empty() {
+                assert_eq!(interval, res.interval(pattern));
+            }
+        }
+    }
+
+    assert!(
+        res.batch_intervals::<&[u8]>(&[], ThreadCount::fixed(2))
+            .is_empty()
+    );
+}
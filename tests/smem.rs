@@ -0,0 +1,139 @@
+use libsais::{SmallAlphabet, bidirectional_fm_index::BidirectionalFmIndex};
+
+mod common;
+
+use common::*;
+
+fn occurs<I: SmallAlphabet>(text: &[I], pattern: &[I]) -> bool {
+    !naive_occurrences(text, pattern).is_empty()
+}
+
+fn naive_smems<I: SmallAlphabet>(
+    text: &[I],
+    query: &[I],
+    min_len: usize,
+) -> Vec<(usize, usize, Vec<usize>)> {
+    let mut smems = Vec::new();
+
+    for start in 0..query.len() {
+        for end in start + 1..=query.len() {
+            let is_super_maximal = occurs(text, &query[start..end])
+                && (start == 0 || !occurs(text, &query[start - 1..end]))
+                && (end == query.len() || !occurs(text, &query[start..end + 1]));
+
+            if is_super_maximal && end - start >= min_len {
+                smems.push((start, end, naive_occurrences(text, &query[start..end])));
+            }
+        }
+    }
+
+    smems
+}
+
+fn naive_mems<I: SmallAlphabet>(
+    text: &[I],
+    query: &[I],
+    min_len: usize,
+) -> Vec<(usize, usize, usize)> {
+    let mut mems = Vec::new();
+
+    for query_start in 0..query.len() {
+        for text_start in 0..text.len() {
+            let is_left_maximal = query_start == 0
+                || text_start == 0
+                || query[query_start - 1] != text[text_start - 1];
+
+            let len = query[query_start..]
+                .iter()
+                .zip(&text[text_start..])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if is_left_maximal && len >= min_len.max(1) {
+                mems.push((query_start, text_start, len));
+            }
+        }
+    }
+
+    mems
+}
+
+fn check_smems_and_mems<I: SmallAlphabet>(text: &[I], queries: &[Vec<I>]) {
+    let index = BidirectionalFmIndex::new(text, 3).expect("libsais should run without an error");
+
+    for query in queries {
+        for min_len in [0, 1, 2, 4] {
+            let smems: Vec<_> = index
+                .smems(query, min_len)
+                .into_iter()
+                .map(|smem| {
+                    let mut positions = index.locate_interval(smem.interval());
+                    positions.sort_unstable();
+
+                    assert_eq!(smem.interval().pattern_len(), smem.len());
+
+                    (smem.query_range().start, smem.query_range().end, positions)
+                })
+                .collect();
+
+            assert_eq!(smems, naive_smems(text, query, min_len));
+
+            let mems: Vec<_> = index
+                .mems(query, min_len)
+                .into_iter()
+                .map(|mem| (mem.query_start(), mem.text_start(), mem.len()))
+                .collect();
+
+            assert_eq!(mems, naive_mems(text, query, min_len));
+        }
+    }
+}
+
+#[test]
+fn smems_and_mems_dna() {
+    let text = b"ACGTACGGACGTTTACGTAACGATTACGGACCA".as_slice();
+
+    let queries = [
+        b"".to_vec(),
+        b"N".to_vec(),
+        b"TACGTTA".to_vec(),
+        b"ACGTACGGACGTTT".to_vec(),
+        b"GGACGTAACGNNTTACG".to_vec(),
+        b"CCAACGT".to_vec(),
+    ];
+
+    check_smems_and_mems(text, &queries);
+}
+
+#[test]
+fn smems_and_mems_basic_example() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let queries = [
+        b"abababcabba".to_vec(),
+        b"babcc".to_vec(),
+        b"cabbabab".to_vec(),
+        b"aaaa".to_vec(),
+    ];
+
+    check_smems_and_mems(text, &queries);
+}
+
+#[test]
+fn smems_and_mems_u16() {
+    let text: Vec<u16> = (0..300u32)
+        .map(|i| ((i * 7 + i / 13) % 9) as u16 * 300)
+        .collect();
+
+    let queries: Vec<Vec<u16>> = vec![
+        text[17..40].to_vec(),
+        text[100..110]
+            .iter()
+            .chain(&[0, 2700])
+            .chain(&text[250..270])
+            .copied()
+            .collect(),
+    ];
+
+    check_smems_and_mems(&text, &queries);
+}
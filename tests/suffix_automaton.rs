@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+
+use libsais::{SuffixArrayConstruction, suffix_automaton::SuffixAutomaton};
+
+mod common;
+
+use common::*;
+
+fn build_automaton(text: &[u8]) -> (Vec<i32>, SuffixAutomaton<u8>) {
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error")
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    let automaton = SuffixAutomaton::new(&res, text);
+
+    (res.suffix_array().to_vec(), automaton)
+}
+
+// the distinct non-empty substrings of the text with their sorted occurrences
+fn naive_substrings(text: &[u8]) -> HashMap<&[u8], Vec<usize>> {
+    let mut substrings = HashMap::new();
+
+    for start in 0..text.len() {
+        for end in start + 1..=text.len() {
+            substrings
+                .entry(&text[start..end])
+                .or_insert_with(|| naive_occurrences(text, &text[start..end]));
+        }
+    }
+
+    substrings
+}
+
+#[test]
+fn empty_and_single_symbol_text_automaton() {
+    let (_, automaton) = build_automaton(b"");
+
+    assert_eq!(automaton.num_states(), 1);
+    assert!(automaton.contains(b""));
+    assert!(!automaton.contains(b"a"));
+    assert_eq!(automaton.longest_common_factor(b"abc"), 0..0);
+
+    let (_, automaton) = build_automaton(b"a");
+
+    assert_eq!(automaton.num_states(), 2);
+    assert!(automaton.contains(b"a"));
+    assert!(!automaton.contains(b"aa"));
+    assert_eq!(automaton.longest_common_factor(b"bab"), 1..2);
+}
+
+#[test]
+fn automaton_states_and_transitions() {
+    for text in [
+        b"abracadabra".as_slice(),
+        b"aaaaaaa",
+        b"mississippi",
+        b"abcabcabcx",
+        b"ACGTTGCAACGTACGT",
+    ] {
+        let (suffix_array, automaton) = build_automaton(text);
+        let substrings = naive_substrings(text);
+
+        // every state is a distinct set of occurrences, the initial state belongs to the empty string
+        let distinct_occurrences: HashSet<_> = substrings.values().collect();
+        assert_eq!(automaton.num_states(), distinct_occurrences.len() + 1);
+        assert!(automaton.num_states() <= 2 * text.len().max(1));
+        assert!(automaton.num_transitions() <= 3 * text.len());
+
+        for (&substring, occurrences) in &substrings {
+            let state = automaton.state(substring).unwrap();
+
+            assert!(automaton.min_len(state) <= substring.len());
+            assert!(substring.len() <= automaton.max_len(state));
+
+            let mut positions: Vec<_> = suffix_array[automaton.interval(state).range()]
+                .iter()
+                .map(|&suffix| suffix as usize)
+                .collect();
+            positions.sort_unstable();
+            assert_eq!(&positions, occurrences);
+
+            for symbol in [b'a', b'c', b'i', b's', b'A', b'T', b'z'] {
+                let extended = [&[symbol], substring].concat();
+                assert_eq!(
+                    automaton.contains(&extended),
+                    substrings.contains_key(extended.as_slice())
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn longest_common_factor() {
+    let text = b"the quick brown fox jumps over the lazy dog".as_slice();
+    let (_, automaton) = build_automaton(text);
+
+    for query in [
+        b"a quick brown cat".as_slice(),
+        b"lazy fox jumps",
+        b"over the lazy",
+        b"xyz",
+        b"",
+        b"dog dog the",
+    ] {
+        let range = automaton.longest_common_factor(query);
+
+        // the leftmost longest common factor, computed naively
+        let mut expected = 0..0;
+        for start in 0..query.len() {
+            for end in start + expected.len() + 1..=query.len() {
+                if !naive_occurrences(text, &query[start..end]).is_empty() {
+                    expected = start..end;
+                }
+            }
+        }
+
+        assert_eq!(range, expected);
+    }
+}
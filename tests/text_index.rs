@@ -0,0 +1,119 @@
+use libsais::{
+    BwtConstruction, SuffixArrayConstruction,
+    bidirectional_fm_index::BidirectionalFmIndex,
+    bwt::AuxIndicesSamplingRate,
+    csa::CompressedSuffixArray,
+    esa::EnhancedSuffixArray,
+    fm_index::FmIndex,
+    position_restricted::PositionRestrictedIndex,
+    r_index::{RIndex, RunLengthBwt},
+    search::LcpLrIndex,
+    text_index::TextIndex,
+};
+
+mod common;
+
+use common::*;
+
+fn check_text_index<T: TextIndex<Symbol = u8>>(index: &T, text: &[u8], patterns: &[&[u8]]) {
+    assert_eq!(index.len(), text.len());
+    assert_eq!(index.is_empty(), text.is_empty());
+
+    for &pattern in patterns {
+        let expected = naive_occurrences(text, pattern);
+
+        assert_eq!(index.count(pattern), expected.len());
+        assert_eq!(index.interval(pattern).len(), expected.len());
+        assert_eq!(index.locate(pattern).len(), expected.len());
+        assert_eq!(index.locate_sorted(pattern), expected);
+    }
+
+    for start in 0..=text.len() {
+        for end in start..=text.len() {
+            assert_eq!(index.extract(start..end), &text[start..end]);
+        }
+    }
+}
+
+#[test]
+fn all_indices_agree() {
+    let text = b"mississippi-missouri-mississauga".as_slice();
+    let patterns = [
+        b"".as_slice(),
+        b"ss",
+        b"issi",
+        b"miss",
+        b"a",
+        b"sauga",
+        b"x",
+        b"i-m",
+    ];
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    check_text_index(&res, text, &patterns);
+    check_text_index(&PositionRestrictedIndex::new(&res), text, &patterns);
+    check_text_index(&CompressedSuffixArray::new(&res, 3), text, &patterns);
+
+    let res = res
+        .plcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais plcp should run without an error")
+        .lcp_construction()
+        .single_threaded()
+        .run()
+        .expect("libsais lcp should run without an error");
+
+    check_text_index(&LcpLrIndex::new(&res, text), text, &patterns);
+    check_text_index(&EnhancedSuffixArray::new(&res, text), text, &patterns);
+
+    let bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    for sampling_rate in [1, 4, 100] {
+        check_text_index(&FmIndex::new(&bwt, sampling_rate), text, &patterns);
+    }
+
+    check_text_index(&RIndex::new(RunLengthBwt::new(&bwt)), text, &patterns);
+    check_text_index(
+        &BidirectionalFmIndex::new(text, 5).expect("libsais should run without an error"),
+        text,
+        &patterns,
+    );
+}
+
+#[test]
+fn fm_index_extract_with_aux_indices() {
+    let (text, _, _, _) = setup_basic_example();
+
+    let bwt = BwtConstruction::for_text(text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .with_aux_indices(AuxIndicesSamplingRate::from(4))
+        .run()
+        .expect("libsais should run without an error");
+
+    let index = FmIndex::from_aux_indices(&bwt);
+
+    check_text_index(&index, text, &[b"ab", b"abba"]);
+}
+
+#[test]
+fn empty_text_index() {
+    let bwt = BwtConstruction::for_text(&[] as &[u8])
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    check_text_index(&FmIndex::new(&bwt, 3), &[], &[b"", b"a"]);
+    check_text_index(&RIndex::new(RunLengthBwt::new(&bwt)), &[], &[b"", b"a"]);
+}
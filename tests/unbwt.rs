@@ -105,3 +105,28 @@ fn unbwt_in_place_with_aux() {
 
     assert_eq!(&text_then_bwt_then_text, text);
 }
+
+#[test]
+fn extract_with_aux() {
+    for text in [
+        b"the quick brown fox jumps over the lazy dog and the quick brown cat".as_slice(),
+        b"abababababababab",
+    ] {
+        for sampling_rate in [2, 4, 16] {
+            let bwt = BwtConstruction::for_text(text)
+                .with_owned_temporary_array_buffer32()
+                .single_threaded()
+                .with_aux_indices(AuxIndicesSamplingRate::from(sampling_rate))
+                .run()
+                .expect("libsais bwt should run without an error");
+
+            let extractor = bwt.extractor();
+
+            for start in 0..=text.len() {
+                for end in start..=text.len() {
+                    assert_eq!(extractor.extract(start..end), &text[start..end]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use libsais::{BwtConstruction, wavelet_matrix::WaveletMatrix};
+
+#[test]
+fn empty_and_constant_wavelet_matrix() {
+    let wavelet_matrix = WaveletMatrix::<u16>::new(&[]);
+
+    assert!(wavelet_matrix.is_empty());
+    assert_eq!(wavelet_matrix.rank(5, 0), 0);
+    assert_eq!(wavelet_matrix.select(5, 0), None);
+    assert!(wavelet_matrix.distinct_in_range(0, 0).is_empty());
+
+    let wavelet_matrix = WaveletMatrix::new(&[0u8; 10]);
+
+    assert_eq!(wavelet_matrix.access(9), 0);
+    assert_eq!(wavelet_matrix.rank(0, 7), 7);
+    assert_eq!(wavelet_matrix.rank(1, 7), 0);
+    assert_eq!(wavelet_matrix.select(0, 3), Some(3));
+    assert_eq!(wavelet_matrix.distinct_in_range(2, 5), vec![(0, 3)]);
+}
+
+#[test]
+fn wavelet_matrix_queries() {
+    let values: Vec<u64> = (0..500u64).map(|i| (i * 31 + i / 7) % 37 * 1000).collect();
+    let wavelet_matrix = WaveletMatrix::new(&values);
+
+    for (i, &value) in values.iter().enumerate() {
+        assert_eq!(wavelet_matrix.access(i), value);
+    }
+
+    for value in (0..40).map(|v| v * 1000).chain([1, 999_999]) {
+        let positions: Vec<_> = (0..values.len()).filter(|&i| values[i] == value).collect();
+
+        for i in (0..=values.len()).step_by(13) {
+            assert_eq!(
+                wavelet_matrix.rank(value, i),
+                positions.iter().filter(|&&position| position < i).count()
+            );
+        }
+
+        for (rank, &position) in positions.iter().enumerate() {
+            assert_eq!(wavelet_matrix.select(value, rank), Some(position));
+        }
+        assert_eq!(wavelet_matrix.select(value, positions.len()), None);
+    }
+
+    for (start, end) in [(0, 500), (17, 18), (100, 250), (499, 500), (3, 3)] {
+        let mut expected: Vec<(u64, usize)> = Vec::new();
+        let mut sorted = values[start..end].to_vec();
+        sorted.sort_unstable();
+
+        for value in sorted {
+            match expected.last_mut() {
+                Some((last, count)) if *last == value => *count += 1,
+                _ => expected.push((value, 1)),
+            }
+        }
+
+        assert_eq!(wavelet_matrix.distinct_in_range(start, end), expected);
+
+        for value in [0, 1, 17_000, 17_001, 36_000, 999_999] {
+            assert_eq!(
+                wavelet_matrix.count_less_than(value, start, end),
+                values[start..end].iter().filter(|&&v| v < value).count()
+            );
+        }
+    }
+}
+
+#[test]
+fn wavelet_matrix_over_u16_bwt() {
+    let text: Vec<u16> = (0..1000u32)
+        .map(|i| ((i * 17 + i / 11) % 400) as u16 * 150)
+        .collect();
+
+    let bwt = BwtConstruction::for_text(&text)
+        .with_owned_temporary_array_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let wavelet_matrix = WaveletMatrix::new(bwt.bwt());
+
+    assert_eq!(wavelet_matrix.len(), text.len());
+
+    for i in (0..text.len()).step_by(7) {
+        let symbol = bwt.bwt()[i];
+        let rank = bwt.bwt()[..i].iter().filter(|&&c| c == symbol).count();
+
+        assert_eq!(wavelet_matrix.access(i), symbol);
+        assert_eq!(wavelet_matrix.rank(symbol, i), rank);
+        assert_eq!(wavelet_matrix.select(symbol, rank), Some(i));
+    }
+}
+
+#[test]
+fn wavelet_matrix_value_range_queries() {
+    let values: Vec<i32> = (0..300).map(|i| (i * 37 + 11) % 301).collect();
+    let wavelet_matrix = WaveletMatrix::new(&values);
+
+    for (start, end) in [(0, 300), (10, 20), (150, 151), (42, 42)] {
+        for (min, max) in [(0, 301), (-5, 17), (100, 200), (250, 1000), (50, 50)] {
+            let mut expected: Vec<_> = values[start..end]
+                .iter()
+                .filter(|&&value| value >= min && value < max)
+                .map(|&value| (value, 1))
+                .collect();
+            expected.sort_unstable();
+
+            assert_eq!(
+                wavelet_matrix.distinct_in_value_range(start, end, min..max),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use libsais::{
+    SuffixArrayConstruction,
+    wildcard::{PatternElement, WildcardPattern, WildcardPatternError},
+};
+
+mod common;
+
+use common::*;
+
+// a backtracking matcher that returns the length of the shortest match starting at position
+fn naive_shortest_match(
+    text: &[u8],
+    elements: &[PatternElement<u8>],
+    position: usize,
+    is_generalized_suffix_array: bool,
+) -> Option<usize> {
+    let Some((element, rest)) = elements.split_first() else {
+        return Some(0);
+    };
+
+    let matches = |symbol: u8| {
+        let is_separator = is_generalized_suffix_array && symbol == 0;
+
+        match element {
+            PatternElement::Symbol(expected) => symbol == *expected,
+            PatternElement::Class(symbols) => symbols.contains(&symbol),
+            PatternElement::NegatedClass(symbols) => !is_separator && !symbols.contains(&symbol),
+            PatternElement::Any | PatternElement::AnySequence => !is_separator,
+        }
+    };
+
+    if *element == PatternElement::AnySequence {
+        let mut shortest: Option<usize> = None;
+        let mut skipped = 0;
+
+        loop {
+            if let Some(len) =
+                naive_shortest_match(text, rest, position + skipped, is_generalized_suffix_array)
+            {
+                shortest = Some(shortest.map_or(skipped + len, |s| s.min(skipped + len)));
+            }
+
+            match text.get(position + skipped) {
+                Some(&symbol) if matches(symbol) => skipped += 1,
+                _ => break,
+            }
+        }
+
+        return shortest;
+    }
+
+    let &symbol = text.get(position)?;
+
+    if !matches(symbol) {
+        return None;
+    }
+
+    naive_shortest_match(text, rest, position + 1, is_generalized_suffix_array).map(|len| len + 1)
+}
+
+fn check_wildcard_matches(text: &[u8], patterns: &[&[u8]], is_generalized_suffix_array: bool) {
+    let mut construction = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded();
+
+    if is_generalized_suffix_array {
+        construction = construction.generalized_suffix_array();
+    }
+
+    let res = construction
+        .run()
+        .expect("libsais should run without an error");
+
+    for &pattern in patterns {
+        let pattern = WildcardPattern::parse(pattern).expect("pattern should be valid");
+
+        let mut found: Vec<(usize, usize)> = res
+            .wildcard_matches(&pattern)
+            .iter()
+            .flat_map(|m| {
+                assert_eq!(m.interval().len(), m.positions().len());
+                m.positions()
+                    .iter()
+                    .map(|&position| (position, m.match_len()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        found.sort_unstable();
+
+        let expected: Vec<_> = (0..=text.len())
+            .filter_map(|position| {
+                naive_shortest_match(
+                    text,
+                    pattern.elements(),
+                    position,
+                    is_generalized_suffix_array,
+                )
+                .map(|len| (position, len))
+            })
+            // the empty match at the end of the text is not part of the suffix array
+            .filter(|&(position, _)| position < text.len())
+            .collect();
+
+        assert_eq!(found, expected);
+    }
+}
+
+#[test]
+fn wildcard_matches_basic_example() {
+    let (text, _, _, _) = setup_basic_example();
+
+    check_wildcard_matches(
+        text,
+        &[
+            b"",
+            b"ab",
+            b"a?a",
+            b"[bc]a",
+            b"[^a]b",
+            b"a*c",
+            b"*c",
+            b"b*",
+            b"?*?b",
+            b"a[a-c]*bb",
+            b"x",
+            b"c?*",
+        ],
+        false,
+    );
+}
+
+#[test]
+fn wildcard_matches_generalized_suffix_array() {
+    let (text, _, _, _) = setup_generalized_suffix_array_example();
+
+    check_wildcard_matches(
+        &text,
+        &[b"a*a", b"c?", b"[^c]c", b"b*c*a", b"ba??", b"?*"],
+        true,
+    );
+}
+
+#[test]
+fn wildcard_matches_empty_sequence() {
+    let text = b"abcab".as_slice();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    for pattern in [b"".as_slice(), b"*", b"**"] {
+        let matches = res.wildcard_matches(&WildcardPattern::parse(pattern).unwrap());
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].match_len(), 0);
+        assert_eq!(matches[0].positions(), &[0, 1, 2, 3, 4]);
+    }
+
+    let empty_text = b"".as_slice();
+
+    let res = SuffixArrayConstruction::for_text(empty_text)
+        .in_owned_buffer32()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    for pattern in [b"".as_slice(), b"*", b"a"] {
+        let matches = res.wildcard_matches(&WildcardPattern::parse(pattern).unwrap());
+
+        assert!(matches.is_empty());
+    }
+}
+
+#[test]
+fn iupac_patterns() {
+    let text = b"ACGTTGCAACGTAGCTAGCATNNACGT".as_slice();
+
+    let res = SuffixArrayConstruction::for_text(text)
+        .in_owned_buffer64()
+        .single_threaded()
+        .run()
+        .expect("libsais should run without an error");
+
+    let pattern = WildcardPattern::from_iupac(b"ASNW").unwrap();
+    let positions: Vec<_> = res
+        .wildcard_matches(&pattern)
+        .iter()
+        .flat_map(|m| m.positions().to_vec())
+        .collect();
+
+    let mut expected: Vec<_> = (0..text.len() - 3)
+        .filter(|&i| {
+            text[i] == b'A'
+                && b"CG".contains(&text[i + 1])
+                && b"ACGT".contains(&text[i + 2])
+                && b"AT".contains(&text[i + 3])
+        })
+        .collect();
+    expected.sort_unstable();
+
+    let mut sorted_positions = positions.clone();
+    sorted_positions.sort_unstable();
+
+    assert_eq!(sorted_positions, expected);
+    assert_eq!(
+        WildcardPattern::from_iupac(b"ACGU").unwrap(),
+        WildcardPattern::parse(b"ACGT").unwrap()
+    );
+}
+
+#[test]
+fn invalid_wildcard_patterns() {
+    assert_eq!(
+        WildcardPattern::parse(b"ab[cd"),
+        Err(WildcardPatternError::UnclosedClass { position: 2 })
+    );
+    assert_eq!(
+        WildcardPattern::parse(b"[]"),
+        Err(WildcardPatternError::EmptyClass { position: 0 })
+    );
+    assert_eq!(
+        WildcardPattern::parse(b"a[z-a]"),
+        Err(WildcardPatternError::InvalidRange { position: 1 })
+    );
+    assert_eq!(
+        WildcardPattern::parse(b"ab\\"),
+        Err(WildcardPatternError::TrailingEscape)
+    );
+    assert_eq!(
+        WildcardPattern::from_iupac(b"ACXT"),
+        Err(WildcardPatternError::InvalidIupacCode { position: 2 })
+    );
+    assert_eq!(
+        WildcardPattern::parse(b"\\*[^-a\\]]").unwrap().elements(),
+        &[
+            PatternElement::Symbol(b'*'),
+            PatternElement::NegatedClass(vec![b'-', b']', b'a'])
+        ]
+    );
+}